@@ -0,0 +1,134 @@
+//! Tamper evidence for the verification history.
+//!
+//! Every stored record carries the hash of the one stored before it
+//! (`VerificationRecord::prev_hash`), and the database keeps the hash of the
+//! newest one as the chain head. Editing, deleting or reordering a record
+//! breaks a link; dropping records from the end no longer matches the head.
+//! When a local Ed25519 key exists the head is also signed, so rewriting the
+//! whole chain needs that key as well.
+
+use anyhow::{Context, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Hash of a record as stored, which the next record points back to.
+pub fn entry_hash(data: &str) -> String {
+    hex::encode(Sha256::digest(data.as_bytes()))
+}
+
+/// The bytes signed for a chain head.
+fn head_message(seq: u64, hash: &str) -> Vec<u8> {
+    format!("verifile-history-head:{}:{}", seq, hash).into_bytes()
+}
+
+/// Sign the chain head `hash` at position `seq`; returns the hex signature.
+pub fn sign_head(key: &SigningKey, seq: u64, hash: &str) -> String {
+    hex::encode(key.sign(&head_message(seq, hash)).to_bytes())
+}
+
+/// Check a hex signature made by `sign_head`.
+pub fn verify_head(key: &VerifyingKey, seq: u64, hash: &str, signature: &str) -> bool {
+    let Ok(bytes) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(sig) = Signature::from_slice(&bytes) else {
+        return false;
+    };
+    key.verify(&head_message(seq, hash), &sig).is_ok()
+}
+
+/// Read the signing key at `path`, if there is one. The file holds the
+/// 32-byte secret as hex.
+pub fn load_key(path: &Path) -> Result<Option<SigningKey>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let bytes: [u8; 32] = hex::decode(text.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .with_context(|| format!("{} is not a VeriFile history key", path.display()))?;
+    Ok(Some(SigningKey::from_bytes(&bytes)))
+}
+
+/// Generate a new signing key and write it to `path`, readable only by the
+/// owner. Fails rather than replace an existing key.
+pub fn create_key(path: &Path) -> Result<SigningKey> {
+    let key = SigningKey::generate(&mut rand_core::OsRng);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path).with_context(|| format!("failed to create {}", path.display()))?;
+    std::io::Write::write_all(&mut file, hex::encode(key.to_bytes()).as_bytes())?;
+    file.sync_all()?;
+    Ok(key)
+}
+
+/// Something `verify-history` found wrong with the chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChainIssue {
+    /// Positions `after + 1 ..= after + count` are gone.
+    Missing { after: u64, count: u64 },
+    /// The record no longer hashes to what was stored for it.
+    Modified { seq: u64, id: String },
+    /// The record does not point at the one before it, so records were
+    /// inserted, removed or reordered here.
+    BrokenLink { seq: u64, id: String },
+    /// A record that was never linked into the chain.
+    Unchained { id: String },
+    /// The stored head is not the last record: records were removed from
+    /// the end, or the head was rewritten.
+    HeadMismatch,
+    /// A signing key exists but the head carries no signature.
+    Unsigned,
+    /// The head signature does not match the key.
+    BadSignature,
+}
+
+impl fmt::Display for ChainIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainIssue::Missing { after, count: 1 } => write!(f, "record #{} is missing", after + 1),
+            ChainIssue::Missing { after, count } => {
+                write!(f, "records #{} to #{} are missing", after + 1, after + count)
+            }
+            ChainIssue::Modified { seq, id } => write!(f, "record #{} ({}) was modified", seq, id),
+            ChainIssue::BrokenLink { seq, id } => write!(
+                f,
+                "record #{} ({}) does not follow the record before it; records were inserted, removed or reordered",
+                seq, id
+            ),
+            ChainIssue::Unchained { id } => write!(f, "record {} was added outside the chain", id),
+            ChainIssue::HeadMismatch => {
+                f.write_str("the chain head does not match the last record; records were removed from the end")
+            }
+            ChainIssue::Unsigned => f.write_str("the chain head is not signed although a signing key exists"),
+            ChainIssue::BadSignature => f.write_str("the chain head signature is not valid for the local key"),
+        }
+    }
+}
+
+/// Result of checking the whole history chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainReport {
+    /// Records walked, including quarantined ones.
+    pub records: u64,
+    /// Hash of the newest record, if any.
+    pub head: Option<String>,
+    /// Whether the head signature was checked and found valid.
+    pub signed: bool,
+    pub issues: Vec<ChainIssue>,
+}
+
+impl ChainReport {
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
@@ -0,0 +1,662 @@
+use verifile::audit;
+use verifile::export::{self, ExportFormat};
+use verifile::file_ops;
+use verifile::import::{self, ImportFormat, ImportOptions};
+use verifile::hashers;
+use verifile::history::{self, HistoryQuery, SortKey};
+use verifile::manifest::{self, EntryStatus, GenerateOptions, ManifestFormat};
+use verifile::models::{Algorithm, VerificationRecord, VerificationStatus};
+use verifile::progress::{self, Progress};
+use verifile::storage;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Every check passed.
+pub const EXIT_OK: i32 = 0;
+/// At least one file did not match or was missing.
+pub const EXIT_MISMATCH: i32 = 1;
+/// Bad arguments or an I/O error; also used by clap for usage errors.
+pub const EXIT_ERROR: i32 = 2;
+
+#[derive(Parser)]
+#[command(name = "verifile", about = "File hash verifier; starts the GUI when no subcommand is given", version)]
+pub struct Cli {
+    /// Print machine-readable JSON instead of human-readable text
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print digests of files, or of stdin when no file (or "-") is given
+    Hash {
+        files: Vec<PathBuf>,
+        /// Hash algorithm; repeat to compute several in one pass
+        #[arg(short, long, default_value = "sha256")]
+        algorithm: Vec<Algorithm>,
+    },
+    /// Verify a file against a reference hash, or every entry of a checksum manifest
+    Check {
+        /// File to verify ("-" for stdin)
+        #[arg(required_unless_present = "manifest", conflicts_with = "manifest")]
+        file: Option<PathBuf>,
+        /// Expected hash in any supported encoding (hex, base64, SRI, sha256:..., multihash)
+        #[arg(short, long, requires = "file")]
+        expect: Option<String>,
+        /// Algorithm to use when the reference does not identify one
+        #[arg(short, long, default_value = "sha256")]
+        algorithm: Algorithm,
+        /// Checksum manifest to verify, like `sha256sum -c` ("-" for stdin)
+        #[arg(short = 'c', long = "manifest")]
+        manifest: Option<PathBuf>,
+        /// Do not add the result to the verification history
+        #[arg(long)]
+        no_record: bool,
+    },
+    /// Write a checksum manifest for files and directory trees
+    Generate {
+        /// Files or directories (walked recursively)
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Hash algorithm, e.g. sha256, blake3, sha512
+        #[arg(short, long, default_value = "sha256")]
+        algorithm: Algorithm,
+        #[arg(short, long, value_enum, default_value_t = FormatArg::Gnu)]
+        format: FormatArg,
+        /// Only include paths matching this glob (repeatable)
+        #[arg(long)]
+        include: Vec<String>,
+        /// Skip paths matching this glob (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+        /// Write absolute paths instead of paths relative to each input
+        #[arg(long)]
+        absolute: bool,
+        /// Output file; defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Re-hash files from earlier history records and report whether they changed
+    Reverify {
+        /// Record ids as shown by `history` (a unique prefix is enough)
+        #[arg(required = true)]
+        ids: Vec<String>,
+        /// Do not add the results to the verification history
+        #[arg(long)]
+        no_record: bool,
+    },
+    /// List past verifications, newest first. History is kept in the user data
+    /// directory, or in $VERIFILE_DATA_DIR if set.
+    History {
+        /// Show at most this many records
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[arg(long, value_enum, default_value_t = SortArg::Date)]
+        sort: SortArg,
+        /// Sort ascending instead of descending
+        #[arg(long)]
+        asc: bool,
+    },
+    /// Export past verifications as CSV, JSON Lines or a standalone HTML report
+    Export {
+        #[arg(short, long, value_enum, default_value_t = ExportArg::Csv)]
+        format: ExportArg,
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Output file; defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Add records from `sha256sum -c` output, hashdeep listings or rhash logs
+    /// to the history
+    Import {
+        #[arg(required = true)]
+        logs: Vec<PathBuf>,
+        /// Log format; detected from the content when omitted
+        #[arg(short, long, value_enum)]
+        format: Option<ImportArg>,
+        /// Algorithm for logs that do not name one
+        #[arg(short, long, default_value = "sha256")]
+        algorithm: Algorithm,
+        /// Resolve relative file names against this directory instead of the log's
+        #[arg(long)]
+        base_dir: Option<PathBuf>,
+        /// The checksum file a check log was made from, to recover its digests
+        #[arg(short, long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Check that no history record was deleted, reordered or modified since
+    /// it was recorded; exits 1 if the chain is broken
+    VerifyHistory {
+        /// Create a local Ed25519 key and sign the history from now on
+        #[arg(long)]
+        create_key: bool,
+    },
+}
+
+/// Filters shared by `history` and `export`.
+#[derive(Args)]
+pub struct FilterArgs {
+    /// Only records whose file name, path or hash contains this text
+    #[arg(short, long)]
+    search: Option<String>,
+    /// Only records with this status (success, failed, unverified, missing, error, ...)
+    #[arg(long)]
+    status: Option<VerificationStatus>,
+    #[arg(short, long)]
+    algorithm: Option<Algorithm>,
+    /// Only records on or after this date (YYYY-MM-DD, local time)
+    #[arg(long)]
+    since: Option<NaiveDate>,
+    /// Only records on or before this date (YYYY-MM-DD, local time)
+    #[arg(long)]
+    until: Option<NaiveDate>,
+}
+
+impl FilterArgs {
+    fn query(self) -> HistoryQuery {
+        HistoryQuery {
+            text: self.search.unwrap_or_default(),
+            status: self.status,
+            algorithm: self.algorithm,
+            from: self.since,
+            to: self.until,
+            ..HistoryQuery::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportArg {
+    /// Output of sha256sum -c, md5sum -c, b2sum -c, ...
+    Sumcheck,
+    Hashdeep,
+    Rhash,
+}
+
+impl From<ImportArg> for ImportFormat {
+    fn from(f: ImportArg) -> Self {
+        match f {
+            ImportArg::Sumcheck => ImportFormat::ShaSumCheck,
+            ImportArg::Hashdeep => ImportFormat::Hashdeep,
+            ImportArg::Rhash => ImportFormat::Rhash,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportArg {
+    Csv,
+    Jsonl,
+    Html,
+}
+
+impl From<ExportArg> for ExportFormat {
+    fn from(f: ExportArg) -> Self {
+        match f {
+            ExportArg::Csv => ExportFormat::Csv,
+            ExportArg::Jsonl => ExportFormat::JsonLines,
+            ExportArg::Html => ExportFormat::Html,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FormatArg {
+    Gnu,
+    Bsd,
+    B3sum,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SortArg {
+    Date,
+    File,
+    Algorithm,
+    Status,
+}
+
+impl From<SortArg> for SortKey {
+    fn from(s: SortArg) -> Self {
+        match s {
+            SortArg::Date => SortKey::Date,
+            SortArg::File => SortKey::FileName,
+            SortArg::Algorithm => SortKey::Algorithm,
+            SortArg::Status => SortKey::Status,
+        }
+    }
+}
+
+impl From<FormatArg> for ManifestFormat {
+    fn from(f: FormatArg) -> Self {
+        match f {
+            FormatArg::Gnu => ManifestFormat::Gnu,
+            FormatArg::Bsd => ManifestFormat::BsdTag,
+            FormatArg::B3sum => ManifestFormat::B3sum,
+            FormatArg::Json => ManifestFormat::Json,
+        }
+    }
+}
+
+fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn record(mut rec: VerificationRecord) {
+    // Stored absolute so `reverify` works from any directory.
+    if let Ok(abs) = std::path::absolute(&rec.file_path) {
+        rec.file_path = abs;
+    }
+    if let Err(e) = open_history().and_then(|store| store.insert(&rec)) {
+        eprintln!("verifile: could not save history: {:#}", e);
+    }
+}
+
+/// Every history record, newest first, passing on any warnings to stderr.
+fn load_history() -> Result<Vec<VerificationRecord>> {
+    let loaded = open_history()?.load()?;
+    for w in &loaded.warnings {
+        eprintln!("verifile: {}", w);
+    }
+    Ok(loaded.records)
+}
+
+/// Open the history database, passing on any warnings to stderr.
+fn open_history() -> Result<storage::History> {
+    let store = storage::History::open_default()?;
+    for w in store.warnings() {
+        eprintln!("verifile: {}", w);
+    }
+    Ok(store)
+}
+
+/// A single self-overwriting status line on stderr, drawn only when stderr is
+/// a terminal so redirected output and CI logs stay clean.
+struct ProgressLine {
+    label: String,
+    progress: Progress,
+    last: Instant,
+    drawn: bool,
+}
+
+impl ProgressLine {
+    fn new(path: &Path) -> Self {
+        let total = if is_stdin(path) { None } else { std::fs::metadata(path).ok().map(|m| m.len()) };
+        ProgressLine { label: path.display().to_string(), progress: Progress::new(total), last: Instant::now(), drawn: false }
+    }
+
+    fn update(&mut self, bytes: u64) {
+        self.progress.update(bytes);
+        if self.last.elapsed() < progress::UPDATE_INTERVAL || !std::io::stderr().is_terminal() {
+            return;
+        }
+        self.last = Instant::now();
+        let percent = self.progress.fraction().map(|f| format!("{:5.1}% ", f * 100.0)).unwrap_or_default();
+        let mut err = std::io::stderr().lock();
+        let _ = write!(err, "\r\x1b[2K{}{}  {}", percent, self.progress.summary(), self.label);
+        let _ = err.flush();
+        self.drawn = true;
+    }
+}
+
+impl Drop for ProgressLine {
+    fn drop(&mut self) {
+        if self.drawn {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
+
+/// Run a headless subcommand and return the process exit code.
+pub fn run(command: Command, json: bool) -> Result<i32> {
+    match command {
+        Command::Hash { files, algorithm } => hash(files, &algorithm, json),
+        Command::Check { file: Some(file), expect, algorithm, no_record, .. } => {
+            check_file(&file, expect.as_deref(), &algorithm, no_record, json)
+        }
+        Command::Check { manifest: Some(manifest), no_record, .. } => check_manifest(&manifest, no_record, json),
+        Command::Check { .. } => unreachable!("clap requires a file or --manifest"),
+        Command::Generate { paths, algorithm, format, include, exclude, absolute, output } => {
+            let format = ManifestFormat::from(format);
+            let algorithm = if format == ManifestFormat::B3sum { Algorithm::Blake3 } else { algorithm };
+            let opts = GenerateOptions { algorithm, include, exclude, absolute_paths: absolute };
+            let lines = manifest::generate_manifest(&paths, &opts)?;
+            let text = manifest::render_manifest(&lines, &opts.algorithm, format)?;
+            match output {
+                Some(path) => std::fs::write(path, text)?,
+                None => print!("{}", text),
+            }
+            Ok(EXIT_OK)
+        }
+        Command::Reverify { ids, no_record } => reverify(&ids, no_record, json),
+        Command::Export { format, filter, output } => {
+            let text = export::export(&load_history()?, &filter.query(), format.into())?;
+            match output {
+                Some(path) => std::fs::write(&path, text).with_context(|| format!("failed to write {}", path.display()))?,
+                None => print!("{}", text),
+            }
+            Ok(EXIT_OK)
+        }
+        Command::Import { logs, format, algorithm, base_dir, manifest } => {
+            import_logs(&logs, format.map(Into::into), &algorithm, base_dir, manifest.as_deref(), json)
+        }
+        Command::VerifyHistory { create_key } => verify_history(create_key, json),
+        Command::History { limit, filter, sort, asc } => {
+            let past = load_history()?;
+            let q = HistoryQuery {
+                sort: sort.into(),
+                descending: !asc,
+                page: 0,
+                page_size: limit.unwrap_or(usize::MAX),
+                ..filter.query()
+            };
+            let shown: Vec<&VerificationRecord> = history::query(&past, &q).indices.into_iter().map(|i| &past[i]).collect();
+            if json {
+                print_json(&shown)?;
+            } else {
+                for r in shown {
+                    println!(
+                        "{}  {}  {:<11} {:<11} {}  {}",
+                        &r.id[..r.id.len().min(8)],
+                        r.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                        r.reverify.map_or(r.status.label(), |o| o.label()),
+                        r.algorithm.name(),
+                        r.computed_hash,
+                        r.file_path.display()
+                    );
+                }
+            }
+            Ok(EXIT_OK)
+        }
+    }
+}
+
+fn hash(files: Vec<PathBuf>, algorithms: &[Algorithm], json: bool) -> Result<i32> {
+    #[derive(Serialize)]
+    struct HashOutput {
+        path: PathBuf,
+        digests: BTreeMap<Algorithm, String>,
+    }
+
+    let files = if files.is_empty() { vec![PathBuf::from("-")] } else { files };
+    let mut outputs = Vec::new();
+    let mut code = EXIT_OK;
+    for path in files {
+        let mut line = ProgressLine::new(&path);
+        let digests = if is_stdin(&path) {
+            hashers::compute_hashes_with_progress(std::io::stdin().lock(), algorithms, &mut |b| line.update(b), None)
+        } else {
+            file_ops::compute_file_hashes_with_progress(&path, algorithms, &mut |b| line.update(b), None)
+        };
+        drop(line);
+        match digests {
+            Ok(digests) => outputs.push(HashOutput { path, digests }),
+            Err(e) => {
+                eprintln!("verifile: {}: {:#}", path.display(), e);
+                code = EXIT_ERROR;
+            }
+        }
+    }
+
+    if json {
+        print_json(&outputs)?;
+    } else {
+        for out in &outputs {
+            for (algo, hex) in &out.digests {
+                if out.digests.len() == 1 {
+                    println!("{}  {}", hex, out.path.display());
+                } else {
+                    println!("{} ({}) = {}", algo.name(), out.path.display(), hex);
+                }
+            }
+        }
+    }
+    Ok(code)
+}
+
+fn check_file(path: &Path, expect: Option<&str>, algorithm: &Algorithm, no_record: bool, json: bool) -> Result<i32> {
+    let mut line = ProgressLine::new(path);
+    let rec = if is_stdin(path) {
+        file_ops::verify_reader_with_progress(std::io::stdin().lock(), path, algorithm, &[], expect, &mut |b| line.update(b), None)?
+    } else {
+        match file_ops::verify_file_with_progress(path, algorithm, &[], expect, &mut |b| line.update(b), None) {
+            Ok(rec) => rec,
+            Err(e) => {
+                drop(line);
+                if !no_record {
+                    record(file_ops::error_record(path, algorithm, expect, &e));
+                }
+                return Err(e.context(format!("failed to read {}", path.display())));
+            }
+        }
+    };
+    drop(line);
+    let code = match rec.status {
+        VerificationStatus::Success | VerificationStatus::Unverified => EXIT_OK,
+        _ => EXIT_MISMATCH,
+    };
+
+    if json {
+        print_json(&rec)?;
+    } else if rec.reference_hash.is_none() {
+        println!("{}  {}", rec.computed_hash, path.display());
+    } else if code == EXIT_OK {
+        println!("{}: OK ({})", path.display(), rec.algorithm.name());
+    } else {
+        println!("{}: FAILED", path.display());
+        eprintln!("verifile: computed {} {}", rec.algorithm.name(), rec.computed_hash);
+    }
+
+    if !no_record && !is_stdin(path) {
+        record(rec);
+    }
+    Ok(code)
+}
+
+fn check_manifest(path: &Path, no_record: bool, json: bool) -> Result<i32> {
+    let report = if is_stdin(path) {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        manifest::verify_manifest_text(&text, path, Path::new("."))?
+    } else {
+        manifest::verify_manifest(path).with_context(|| format!("failed to read {}", path.display()))?
+    };
+    let summary = report.summary();
+
+    if json {
+        print_json(&report)?;
+    } else {
+        for r in &report.results {
+            let name = r.entry.file_name.as_deref().unwrap_or_default();
+            match &r.error {
+                Some(err) => println!("{}: {} ({})", name, r.status.label(), err),
+                None => println!("{}: {}", name, r.status.label()),
+            }
+        }
+        for d in &report.diagnostics {
+            eprintln!("verifile: {}: line {}: {}", path.display(), d.line, d.message);
+        }
+        if summary.failed > 0 {
+            eprintln!("verifile: WARNING: {} computed checksum(s) did NOT match", summary.failed);
+        }
+        if summary.missing > 0 {
+            eprintln!("verifile: WARNING: {} listed file(s) could not be found", summary.missing);
+        }
+    }
+
+    let all_ok = summary.total > 0 && report.results.iter().all(|r| r.status == EntryStatus::Ok);
+    if !no_record && !is_stdin(path) {
+        record(report.history_record());
+    }
+    Ok(if all_ok { EXIT_OK } else { EXIT_MISMATCH })
+}
+
+#[derive(Serialize)]
+struct ImportResult {
+    log: PathBuf,
+    /// `None` when this log was imported before.
+    imported: Option<usize>,
+    skipped_lines: usize,
+}
+
+fn import_logs(
+    logs: &[PathBuf],
+    format: Option<ImportFormat>,
+    algorithm: &Algorithm,
+    base_dir: Option<PathBuf>,
+    manifest: Option<&Path>,
+    json: bool,
+) -> Result<i32> {
+    let checksums = match manifest {
+        Some(path) => {
+            let text = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+            Some(manifest::parse_checksum_text(&text, manifest::algorithm_hint_from_path(path).as_ref()))
+        }
+        None => None,
+    };
+    let store = open_history()?;
+    let mut results = Vec::new();
+    for log in logs {
+        let mut opts = ImportOptions::for_log(log);
+        opts.algorithm = algorithm.clone();
+        opts.manifest = checksums.clone();
+        if let Some(dir) = &base_dir {
+            opts.base_dir = std::path::absolute(dir)?;
+        }
+        let parsed = import::import_file(log, format, opts)?;
+        for d in &parsed.diagnostics {
+            eprintln!("verifile: {}:{}: {}", log.display(), d.line, d.message);
+        }
+        let imported = store.import_log_once(log, &parsed.records)?;
+        if !json {
+            match imported {
+                Some(n) => println!("{}: imported {} records", log.display(), n),
+                None => println!("{}: already imported, skipped", log.display()),
+            }
+        }
+        results.push(ImportResult { log: log.clone(), imported, skipped_lines: parsed.diagnostics.len() });
+    }
+    if json {
+        print_json(&results)?;
+    }
+    Ok(EXIT_OK)
+}
+
+fn verify_history(create_key: bool, json: bool) -> Result<i32> {
+    let mut store = open_history()?;
+    if create_key {
+        let path = storage::key_path();
+        let key = audit::create_key(&path)?;
+        eprintln!(
+            "verifile: created {} (public key {})",
+            path.display(),
+            hex::encode(key.verifying_key().to_bytes())
+        );
+        store.set_signing_key(Some(key));
+        store.sign_head()?;
+    }
+    let report = store.verify_chain()?;
+    if json {
+        print_json(&report)?;
+    } else {
+        for issue in &report.issues {
+            println!("BROKEN: {}", issue);
+        }
+        let head = report.head.as_deref().unwrap_or("none");
+        println!(
+            "{} records, head {}{}: {}",
+            report.records,
+            head,
+            if report.signed { " (signed)" } else { "" },
+            if report.is_intact() { "intact" } else { "TAMPERED" }
+        );
+    }
+    Ok(if report.is_intact() { EXIT_OK } else { EXIT_MISMATCH })
+}
+
+fn reverify(ids: &[String], no_record: bool, json: bool) -> Result<i32> {
+    let store = open_history()?;
+    let mut originals = Vec::new();
+    for id in ids {
+        let matches = store.find_by_id_prefix(id)?;
+        match matches.as_slice() {
+            [only] => originals.push(only.clone()),
+            [] => anyhow::bail!("no history record with id {}", id),
+            _ => anyhow::bail!("id {} matches {} records; use more characters", id, matches.len()),
+        }
+    }
+
+    let mut code = EXIT_OK;
+    let mut results = Vec::new();
+    for original in &originals {
+        let mut line = ProgressLine::new(&original.file_path);
+        let result = file_ops::reverify_with_progress(original, &mut |b| line.update(b), None);
+        drop(line);
+        // Keep going, so the records already re-verified are still saved.
+        let rec = match result {
+            Ok(rec) => rec,
+            Err(e) => {
+                eprintln!("verifile: {}: {:#}", original.file_path.display(), e);
+                code = EXIT_ERROR;
+                continue;
+            }
+        };
+        match &rec.status {
+            VerificationStatus::Success => {}
+            VerificationStatus::Error { message, .. } => {
+                eprintln!("verifile: {}: {}", rec.file_path.display(), message);
+                code = EXIT_ERROR;
+            }
+            _ if code == EXIT_OK => code = EXIT_MISMATCH,
+            _ => {}
+        }
+        if let (false, Some(outcome)) = (json, rec.reverify) {
+            let since = original.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+            let outcome = outcome.label();
+            let changes = match (&rec.metadata, &original.metadata) {
+                (Some(now), Some(then)) => now.changes_since(then),
+                _ => Vec::new(),
+            };
+            if changes.is_empty() {
+                println!("{}: {} since {} ({})", rec.file_path.display(), outcome.to_uppercase(), since, rec.algorithm.name());
+            } else {
+                println!(
+                    "{}: {} since {} ({}; {} changed)",
+                    rec.file_path.display(),
+                    outcome.to_uppercase(),
+                    since,
+                    rec.algorithm.name(),
+                    changes.join(", ")
+                );
+            }
+        }
+        results.push(rec);
+    }
+    if json {
+        print_json(&results)?;
+    }
+    if !no_record {
+        for rec in &results {
+            if let Err(e) = store.insert(rec) {
+                eprintln!("verifile: could not save history: {:#}", e);
+            }
+        }
+    }
+    Ok(code)
+}
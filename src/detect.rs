@@ -0,0 +1,34 @@
+use crate::encoding::{self, DecodedReference};
+use crate::models::{Algorithm, HashEncoding};
+
+fn candidates_for(decoded: &DecodedReference) -> Vec<Algorithm> {
+    match &decoded.algorithm {
+        Some(a) => vec![a.clone()],
+        None => Algorithm::all()
+            .into_iter()
+            .filter(|a| a.output_len() == decoded.bytes.len())
+            .collect(),
+    }
+}
+
+/// Infer which algorithms could have produced `reference`, from an explicit
+/// label if present, otherwise from the decoded digest length.
+/// Returns an empty list if nothing fits.
+pub fn candidate_algorithms(reference: &str) -> Vec<Algorithm> {
+    encoding::decode_reference(reference)
+        .iter()
+        .map(candidates_for)
+        .find(|c| !c.is_empty())
+        .unwrap_or_default()
+}
+
+/// Compare a reference hash in any supported encoding against a computed hex
+/// digest. Returns the encoding the reference used if it matches.
+pub fn match_reference(reference: &str, algorithm: &Algorithm, computed_hex: &str) -> Option<HashEncoding> {
+    let computed = hex::decode(computed_hex).ok()?;
+    encoding::decode_reference(reference)
+        .into_iter()
+        .filter(|d| d.algorithm.as_ref().is_none_or(|a| a == algorithm))
+        .find(|d| encoding::constant_time_eq(&d.bytes, &computed))
+        .map(|d| d.encoding)
+}
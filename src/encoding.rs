@@ -0,0 +1,193 @@
+use crate::models::{Algorithm, HashEncoding};
+use data_encoding::{BASE32, BASE32_NOPAD, BASE64, BASE64URL, BASE64URL_NOPAD, BASE64_NOPAD};
+
+/// One plausible reading of a reference hash as raw digest bytes.
+#[derive(Debug, Clone)]
+pub struct DecodedReference {
+    pub bytes: Vec<u8>,
+    pub encoding: HashEncoding,
+    /// Algorithm named by the reference itself (SRI, `sha256:` label, multihash).
+    pub algorithm: Option<Algorithm>,
+}
+
+/// Decode a pasted reference hash into every plausible digest, most likely first.
+///
+/// Accepts plain hex, base64, RFC 4648 base32, Nix base32, SRI (`sha256-...`),
+/// labelled digests (`sha256:...`, as used by Docker and Nix) and IPFS
+/// multihashes (`Qm...` or a multibase CIDv1).
+pub fn decode_reference(reference: &str) -> Vec<DecodedReference> {
+    let t = reference.trim();
+    if t.is_empty() {
+        return Vec::new();
+    }
+
+    // "<alg>-<base64>" is SRI; "<alg>:<digest>" is a labelled digest in any encoding.
+    for sep in [':', '-'] {
+        if let Some((label, rest)) = t.split_once(sep) {
+            if let Ok(algo) = label.parse::<Algorithm>() {
+                let mut out = decode_bare(rest.trim(), Some(&algo));
+                if sep == '-' {
+                    for d in out.iter_mut().filter(|d| d.encoding == HashEncoding::Base64) {
+                        d.encoding = HashEncoding::Sri;
+                    }
+                }
+                for d in out.iter_mut() {
+                    d.algorithm = Some(algo.clone());
+                }
+                return out;
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    if let Some(d) = decode_multihash(t) {
+        out.push(d);
+    }
+    out.extend(decode_bare(t, None));
+    out
+}
+
+/// Decode an unlabelled digest, keeping only readings whose length fits
+/// `algorithm` (or any registered algorithm when none is given).
+fn decode_bare(t: &str, algorithm: Option<&Algorithm>) -> Vec<DecodedReference> {
+    let fits = |len: usize| match algorithm {
+        Some(a) => a.output_len() == len,
+        None => Algorithm::all().iter().any(|a| a.output_len() == len),
+    };
+    let mut out = Vec::new();
+    let mut push = |bytes: Vec<u8>, encoding: HashEncoding| {
+        if fits(bytes.len()) {
+            out.push(DecodedReference { bytes, encoding, algorithm: None });
+        }
+    };
+
+    if let Ok(bytes) = hex::decode(t) {
+        push(bytes, HashEncoding::Hex);
+    }
+    if let Some(bytes) = nix_base32_decode(t) {
+        push(bytes, HashEncoding::NixBase32);
+    }
+    for spec in [&BASE64, &BASE64_NOPAD, &BASE64URL, &BASE64URL_NOPAD] {
+        if let Ok(bytes) = spec.decode(t.as_bytes()) {
+            push(bytes, HashEncoding::Base64);
+            break;
+        }
+    }
+    let upper = t.to_ascii_uppercase();
+    for spec in [&BASE32, &BASE32_NOPAD] {
+        if let Ok(bytes) = spec.decode(upper.as_bytes()) {
+            push(bytes, HashEncoding::Base32);
+            break;
+        }
+    }
+    out
+}
+
+const NIX_BASE32_CHARS: &[u8] = b"0123456789abcdfghijklmnpqrsvwxyz";
+
+/// Nix's base32 variant: custom alphabet, least significant bits first,
+/// characters written in reverse.
+fn nix_base32_decode(s: &str) -> Option<Vec<u8>> {
+    let input = s.as_bytes();
+    let len = input.len() * 5 / 8;
+    if len == 0 || (len * 8).div_ceil(5) != input.len() {
+        return None;
+    }
+    let mut out = vec![0u8; len];
+    for (n, &c) in input.iter().rev().enumerate() {
+        let digit = NIX_BASE32_CHARS.iter().position(|&x| x == c)? as u16;
+        let b = n * 5;
+        let i = b / 8;
+        let j = b % 8;
+        out[i] |= (digit << j) as u8;
+        let carry = (digit << j) >> 8;
+        if i + 1 < len {
+            out[i + 1] |= carry as u8;
+        } else if carry != 0 {
+            return None;
+        }
+    }
+    Some(out)
+}
+
+/// Multicodec identifiers for the hash functions we support.
+const MULTIHASH_CODES: &[(u64, Algorithm)] = &[
+    (0x11, Algorithm::Sha1),
+    (0x12, Algorithm::Sha256),
+    (0x13, Algorithm::Sha512),
+    (0x14, Algorithm::Sha3_512),
+    (0x15, Algorithm::Sha3_384),
+    (0x16, Algorithm::Sha3_256),
+    (0x17, Algorithm::Sha3_224),
+    (0x1b, Algorithm::Keccak256),
+    (0x1e, Algorithm::Blake3),
+    (0x20, Algorithm::Sha384),
+    (0xd5, Algorithm::Md5),
+    (0x1013, Algorithm::Sha224),
+    (0x1015, Algorithm::Sha512_256),
+    (0xb240, Algorithm::Blake2b512),
+    (0xb260, Algorithm::Blake2s256),
+];
+
+/// Decode a CIDv0 (`Qm...`) or a multibase-prefixed CIDv1 / multihash.
+fn decode_multihash(t: &str) -> Option<DecodedReference> {
+    let raw = if t.starts_with("Qm") {
+        bs58::decode(t).into_vec().ok()?
+    } else {
+        let mut chars = t.chars();
+        let prefix = chars.next()?;
+        let body = chars.as_str();
+        match prefix {
+            'z' => bs58::decode(body).into_vec().ok()?,
+            'b' | 'B' => BASE32_NOPAD.decode(body.to_ascii_uppercase().as_bytes()).ok()?,
+            'f' | 'F' => hex::decode(body).ok()?,
+            'm' => BASE64_NOPAD.decode(body.as_bytes()).ok()?,
+            'u' => BASE64URL_NOPAD.decode(body.as_bytes()).ok()?,
+            _ => return None,
+        }
+    };
+
+    // CIDv1 wraps the multihash in <version><content codec>.
+    let mut cid = raw.as_slice();
+    if read_varint(&mut cid) == Some(1) && read_varint(&mut cid).is_some() {
+        if let Some(d) = parse_multihash(cid) {
+            return Some(d);
+        }
+    }
+    parse_multihash(&raw)
+}
+
+fn parse_multihash(mut data: &[u8]) -> Option<DecodedReference> {
+    let code = read_varint(&mut data)?;
+    let len = read_varint(&mut data)? as usize;
+    let algorithm = MULTIHASH_CODES.iter().find(|(c, _)| *c == code)?.1.clone();
+    if data.len() != len || len != algorithm.output_len() {
+        return None;
+    }
+    Some(DecodedReference {
+        bytes: data.to_vec(),
+        encoding: HashEncoding::Multihash,
+        algorithm: Some(algorithm),
+    })
+}
+
+/// Unsigned LEB128, as used by multiformats.
+fn read_varint(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, &byte) in data.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *data = &data[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Compare two digests without short-circuiting on the first differing byte.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,251 @@
+//! Export the verification history for people who do not run VeriFile:
+//! CSV for spreadsheets, JSON Lines for scripts, and a standalone HTML
+//! report with pass/fail totals.
+
+use crate::history::HistoryQuery;
+use crate::models::{VerificationRecord, VerificationStatus};
+use anyhow::Result;
+use chrono::Local;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// One JSON record per line.
+    JsonLines,
+    /// A single HTML file with inline styles and no external resources.
+    Html,
+}
+
+impl ExportFormat {
+    pub fn all() -> Vec<ExportFormat> {
+        vec![ExportFormat::Csv, ExportFormat::JsonLines, ExportFormat::Html]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::JsonLines => "JSON Lines",
+            ExportFormat::Html => "HTML report",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Render every record in `records` that `q` matches, sorted as `q` asks;
+/// paging in `q` is ignored.
+pub fn export(records: &[VerificationRecord], q: &HistoryQuery, format: ExportFormat) -> Result<String> {
+    let selected = select(records, q);
+    match format {
+        ExportFormat::Csv => Ok(render_csv(&selected)),
+        ExportFormat::JsonLines => render_jsonl(&selected),
+        ExportFormat::Html => Ok(render_html(&selected, q)),
+    }
+}
+
+fn select<'a>(records: &'a [VerificationRecord], q: &HistoryQuery) -> Vec<&'a VerificationRecord> {
+    let all_pages = HistoryQuery { page: 0, page_size: usize::MAX, ..q.clone() };
+    crate::history::query(records, &all_pages).indices.into_iter().map(|i| &records[i]).collect()
+}
+
+/// Status as shown to users: the re-verification outcome when there is one.
+fn outcome(rec: &VerificationRecord) -> &'static str {
+    rec.reverify.map_or(rec.status.label(), |o| o.label())
+}
+
+const CSV_HEADER: &[&str] = &[
+    "id",
+    "timestamp",
+    "file_name",
+    "file_path",
+    "algorithm",
+    "status",
+    "computed_hash",
+    "reference_hash",
+    "integrity_only",
+    "parent_id",
+];
+
+/// RFC 4180 CSV with a header row; timestamps in UTC, RFC 3339.
+fn render_csv(records: &[&VerificationRecord]) -> String {
+    let mut out = CSV_HEADER.join(",");
+    out.push_str("\r\n");
+    for r in records {
+        let fields = [
+            r.id.clone(),
+            r.timestamp.to_rfc3339(),
+            r.file_name.clone(),
+            r.file_path.display().to_string(),
+            r.algorithm.name().to_string(),
+            outcome(r).to_string(),
+            r.computed_hash.clone(),
+            r.reference_hash.clone().unwrap_or_default(),
+            r.integrity_only.to_string(),
+            r.parent_id.clone().unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&line.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// One CSV cell. Text a spreadsheet would evaluate as a formula (a file
+/// name starting with `=`, `+`, `-`, `@`, a tab or a carriage return) gets
+/// a leading `'`.
+fn csv_field(s: &str) -> String {
+    let s = if s.starts_with(['=', '+', '-', '@', '\t', '\r']) { format!("'{}", s) } else { s.to_string() };
+    if s.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
+
+fn render_jsonl(records: &[&VerificationRecord]) -> Result<String> {
+    let mut out = String::new();
+    for r in records {
+        out.push_str(&serde_json::to_string(r)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn html_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// The filters in `q`, e.g. "status Failed, since 2024-01-01", for the report header.
+fn describe_filters(q: &HistoryQuery) -> String {
+    let mut parts = Vec::new();
+    if !q.text.trim().is_empty() {
+        parts.push(format!("matching \"{}\"", q.text.trim()));
+    }
+    if let Some(s) = &q.status {
+        parts.push(format!("status {}", s));
+    }
+    if let Some(a) = &q.algorithm {
+        parts.push(format!("algorithm {}", a));
+    }
+    if let Some(d) = q.from {
+        parts.push(format!("since {}", d));
+    }
+    if let Some(d) = q.to {
+        parts.push(format!("until {}", d));
+    }
+    if parts.is_empty() {
+        "all records".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+const HTML_STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+h1 { margin-bottom: 0.2em; }
+.meta { color: #666; margin-top: 0; }
+.summary { display: flex; gap: 1em; margin: 1.5em 0; flex-wrap: wrap; }
+.card { border: 1px solid #ddd; border-radius: 6px; padding: 0.8em 1.2em; min-width: 7em; }
+.card .n { font-size: 1.8em; font-weight: 600; }
+table { border-collapse: collapse; width: 100%; font-size: 0.9em; }
+th, td { text-align: left; padding: 0.4em 0.6em; border-bottom: 1px solid #eee; vertical-align: top; }
+th { background: #f5f5f5; }
+.path { color: #777; font-size: 0.85em; }
+.hash { font-family: ui-monospace, monospace; word-break: break-all; }
+.pass { color: #1a7f37; font-weight: 600; }
+.fail { color: #c62828; font-weight: 600; }
+.other { color: #8a6d00; font-weight: 600; }
+@media print { body { margin: 0; } }
+";
+
+fn status_class(rec: &VerificationRecord) -> &'static str {
+    match (rec.reverify, &rec.status) {
+        (Some(crate::models::ReverifyOutcome::Unchanged), _) | (None, VerificationStatus::Success) => "pass",
+        (Some(_), _) | (None, VerificationStatus::Failed | VerificationStatus::Missing | VerificationStatus::Error { .. }) => "fail",
+        _ => "other",
+    }
+}
+
+fn render_html(records: &[&VerificationRecord], q: &HistoryQuery) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for r in records {
+        *counts.entry(outcome(r)).or_default() += 1;
+    }
+    let passed = records.iter().filter(|r| status_class(r) == "pass").count();
+    let failed = records.iter().filter(|r| status_class(r) == "fail").count();
+
+    let mut out = String::new();
+    // Writing to a String cannot fail.
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>VeriFile verification report</title>\n<style>{}</style>\n</head>\n<body>\n\
+         <h1>VeriFile verification report</h1>\n\
+         <p class=\"meta\">Generated {} &middot; {}</p>\n",
+        HTML_STYLE,
+        Local::now().format("%Y-%m-%d %H:%M:%S %Z"),
+        html_escape(&describe_filters(q)),
+    );
+
+    out.push_str("<div class=\"summary\">\n");
+    let card = |out: &mut String, label: &str, n: usize, class: &str| {
+        let _ = writeln!(out, "<div class=\"card\"><div class=\"n {}\">{}</div>{}</div>", class, n, html_escape(label));
+    };
+    card(&mut out, "Records", records.len(), "");
+    card(&mut out, "Passed", passed, "pass");
+    card(&mut out, "Failed", failed, "fail");
+    for (label, n) in &counts {
+        card(&mut out, label, *n, "");
+    }
+    out.push_str("</div>\n");
+
+    out.push_str(
+        "<table>\n<thead><tr><th>Date</th><th>File</th><th>Algorithm</th><th>Status</th>\
+         <th>Computed hash</th><th>Reference</th></tr></thead>\n<tbody>\n",
+    );
+    for r in records {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}<div class=\"path\">{}</div></td><td>{}</td><td class=\"{}\">{}</td>\
+             <td class=\"hash\">{}</td><td class=\"hash\">{}</td></tr>",
+            r.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+            html_escape(&r.file_name),
+            html_escape(&r.file_path.display().to_string()),
+            html_escape(r.algorithm.name()),
+            status_class(r),
+            outcome(r),
+            html_escape(&r.computed_hash),
+            html_escape(r.reference_hash.as_deref().unwrap_or("")),
+        );
+    }
+    if records.is_empty() {
+        out.push_str("<tr><td colspan=\"6\">No matching verifications</td></tr>\n");
+    }
+    out.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    out
+}
@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use anyhow::Result;
 use std::path::Path;
-use crate::hashers;
-use crate::models::Algorithm;
-use std::io::BufReader;
+use crate::detect;
+use crate::hashers::{self, CancelToken};
+use crate::manifest;
+use crate::models::{Algorithm, FileMetadata, HashEncoding, ReverifyOutcome, VerificationRecord, VerificationStatus};
+use std::io::{BufReader, Read};
+use chrono::Utc;
+use uuid::Uuid;
 
 /// Compute hash of the file at path using streaming read.
 /// This is synchronous; call it inside a spawned thread/task to keep UI responsive.
@@ -13,3 +18,262 @@ pub fn compute_file_hash(path: &Path, algo: &Algorithm) -> Result<String> {
     let hex = hashers::compute_hash_for_reader(reader, algo)?;
     Ok(hex)
 }
+
+/// Compute several digests of the file at path while reading it only once.
+pub fn compute_file_hashes(path: &Path, algos: &[Algorithm]) -> Result<BTreeMap<Algorithm, String>> {
+    compute_file_hashes_with_progress(path, algos, &mut |_| {}, None)
+}
+
+/// `compute_file_hashes`, reporting bytes hashed so far to `progress`.
+/// See `hashers::compute_hashes_with_progress`.
+pub fn compute_file_hashes_with_progress(
+    path: &Path,
+    algos: &[Algorithm],
+    progress: &mut dyn FnMut(u64),
+    cancel: Option<&CancelToken>,
+) -> Result<BTreeMap<Algorithm, String>> {
+    let f = File::open(path)?;
+    let reader = BufReader::new(f);
+    hashers::compute_hashes_with_progress(reader, algos, progress, cancel)
+}
+
+/// Hash the file at path and compare it against an optional reference hash.
+/// See `verify_reader`.
+pub fn verify_file(path: &Path, algo: &Algorithm, extras: &[Algorithm], reference: Option<&str>) -> Result<VerificationRecord> {
+    verify_file_with_progress(path, algo, extras, reference, &mut |_| {}, None)
+}
+
+/// `verify_file`, reporting bytes hashed so far to `progress` and stopping
+/// with `hashers::Cancelled` once `cancel` is triggered. The file's metadata
+/// is taken from the open handle before hashing starts.
+pub fn verify_file_with_progress(
+    path: &Path,
+    algo: &Algorithm,
+    extras: &[Algorithm],
+    reference: Option<&str>,
+    progress: &mut dyn FnMut(u64),
+    cancel: Option<&CancelToken>,
+) -> Result<VerificationRecord> {
+    let f = File::open(path)?;
+    let metadata = f.metadata().ok().map(|m| FileMetadata::from(&m));
+    let mut rec = verify_reader_with_progress(BufReader::new(f), path, algo, extras, reference, progress, cancel)?;
+    rec.metadata = metadata;
+    Ok(rec)
+}
+
+/// Hash `reader` with `algo` plus `extras` in a single pass and build a record.
+///
+/// Every candidate algorithm for the reference (one if it is labelled, several
+/// for e.g. 64 bare hex chars) is hashed in the same pass and the record
+/// reports whichever one matched; otherwise `algo` is used.
+pub fn verify_reader<R: Read>(reader: R, path: &Path, algo: &Algorithm, extras: &[Algorithm], reference: Option<&str>) -> Result<VerificationRecord> {
+    verify_reader_with_progress(reader, path, algo, extras, reference, &mut |_| {}, None)
+}
+
+/// `verify_reader` with progress reporting and cancellation, as for
+/// `verify_file_with_progress`.
+pub fn verify_reader_with_progress<R: Read>(
+    reader: R,
+    path: &Path,
+    algo: &Algorithm,
+    extras: &[Algorithm],
+    reference: Option<&str>,
+    progress: &mut dyn FnMut(u64),
+    cancel: Option<&CancelToken>,
+) -> Result<VerificationRecord> {
+    let reference = reference.map(str::trim).filter(|r| !r.is_empty());
+    let candidates = reference.map(detect::candidate_algorithms).unwrap_or_default();
+    let mut algos = vec![algo.clone()];
+    for a in extras.iter().chain(&candidates) {
+        if !algos.contains(a) {
+            algos.push(a.clone());
+        }
+    }
+    let mut digests = hashers::compute_hashes_with_progress(reader, &algos, progress, cancel)?;
+
+    let matched = reference.and_then(|rh| {
+        std::iter::once(algo)
+            .chain(candidates.iter())
+            .find(|a| digests.get(*a).is_some_and(|h| detect::match_reference(rh, a, h).is_some()))
+    });
+    let algo = matched.unwrap_or(algo).clone();
+    let hex = digests.get(&algo).cloned().unwrap_or_default();
+    if digests.len() == 1 {
+        digests.clear();
+    }
+
+    let reference_encoding = reference.and_then(|rh| detect::match_reference(rh, &algo, &hex));
+    let status = match (reference, reference_encoding) {
+        (None, _) => VerificationStatus::Unverified,
+        (Some(_), None) => VerificationStatus::Failed,
+        (Some(_), Some(_)) => VerificationStatus::Success,
+    };
+    Ok(VerificationRecord {
+        id: Uuid::new_v4().to_string(),
+        file_name: path.file_name().and_then(|s| s.to_str()).unwrap_or("file").to_string(),
+        file_path: path.to_path_buf(),
+        integrity_only: algo.is_integrity_only(),
+        algorithm: algo,
+        computed_hash: hex,
+        reference_hash: reference.map(String::from),
+        reference_encoding,
+        digests,
+        manifest: None,
+        metadata: None,
+        parent_id: None,
+        reverify: None,
+        prev_hash: None,
+        status,
+        timestamp: Utc::now(),
+    })
+}
+
+/// History entry for a verification the user stopped before it finished.
+pub fn cancelled_record(path: &Path, algo: &Algorithm, reference: Option<&str>) -> VerificationRecord {
+    unhashed_record(path, algo, reference, VerificationStatus::Cancelled)
+}
+
+/// History entry for a re-verification of `original` that was cancelled,
+/// linked to it by `parent_id`.
+pub fn cancelled_reverify_record(original: &VerificationRecord) -> VerificationRecord {
+    VerificationRecord {
+        file_name: original.file_name.clone(),
+        parent_id: Some(original.id.clone()),
+        ..cancelled_record(&original.file_path, &original.algorithm, Some(&original.computed_hash))
+    }
+}
+
+/// History entry for a file that could not be hashed: `Missing` or `Error`
+/// as `VerificationStatus::from_error` decides.
+pub fn error_record(path: &Path, algo: &Algorithm, reference: Option<&str>, err: &anyhow::Error) -> VerificationRecord {
+    unhashed_record(path, algo, reference, VerificationStatus::from_error(err))
+}
+
+fn unhashed_record(path: &Path, algo: &Algorithm, reference: Option<&str>, status: VerificationStatus) -> VerificationRecord {
+    VerificationRecord {
+        id: Uuid::new_v4().to_string(),
+        file_name: path.file_name().and_then(|s| s.to_str()).unwrap_or("file").to_string(),
+        file_path: path.to_path_buf(),
+        algorithm: algo.clone(),
+        computed_hash: String::new(),
+        reference_hash: reference.map(str::trim).filter(|r| !r.is_empty()).map(String::from),
+        reference_encoding: None,
+        digests: BTreeMap::new(),
+        integrity_only: algo.is_integrity_only(),
+        manifest: None,
+        metadata: None,
+        parent_id: None,
+        reverify: None,
+        prev_hash: None,
+        status,
+        timestamp: Utc::now(),
+    }
+}
+
+/// Re-hash `original.file_path` with the stored algorithm and compare it
+/// against the stored digest, producing a new record linked by `parent_id`.
+///
+/// A changed digest is reported as `Corrupted` rather than `Changed` when
+/// the original record has metadata and the file's size and modification
+/// time still match it. A file that cannot be read gives an `Error` record.
+///
+/// A manifest record is re-verified by checking every entry of its checksum
+/// file again. Cancellation and records without a stored digest (cancelled
+/// or failed checks) are returned as errors.
+pub fn reverify(original: &VerificationRecord) -> Result<VerificationRecord> {
+    reverify_with_progress(original, &mut |_| {}, None)
+}
+
+/// `reverify` with progress reporting and cancellation, as for
+/// `verify_file_with_progress`.
+pub fn reverify_with_progress(
+    original: &VerificationRecord,
+    progress: &mut dyn FnMut(u64),
+    cancel: Option<&CancelToken>,
+) -> Result<VerificationRecord> {
+    if original.manifest.is_some() {
+        return reverify_manifest(original, progress, cancel);
+    }
+    if original.computed_hash.is_empty() {
+        anyhow::bail!("record {} has no stored digest to re-verify against", original.id);
+    }
+    let algo = &original.algorithm;
+    let (outcome, hex, metadata) = match File::open(&original.file_path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (ReverifyOutcome::Missing, String::new(), None),
+        Err(e) => return Ok(reverify_error(original, &e.into())),
+        Ok(f) => {
+            let metadata = f.metadata().ok().map(|m| FileMetadata::from(&m));
+            let mut digests = match hashers::compute_hashes_with_progress(BufReader::new(f), std::slice::from_ref(algo), progress, cancel) {
+                Err(e) if e.is::<hashers::Cancelled>() => return Err(e),
+                Err(e) => return Ok(reverify_error(original, &e)),
+                Ok(digests) => digests,
+            };
+            let hex = digests.remove(algo).unwrap_or_default();
+            let unwritten = match (&metadata, &original.metadata) {
+                (Some(now), Some(then)) => now.looks_unwritten_since(then),
+                _ => false,
+            };
+            let outcome = if hex.eq_ignore_ascii_case(&original.computed_hash) {
+                ReverifyOutcome::Unchanged
+            } else if unwritten {
+                ReverifyOutcome::Corrupted
+            } else {
+                ReverifyOutcome::Changed
+            };
+            (outcome, hex, metadata)
+        }
+    };
+    Ok(VerificationRecord {
+        id: Uuid::new_v4().to_string(),
+        file_name: original.file_name.clone(),
+        file_path: original.file_path.clone(),
+        algorithm: algo.clone(),
+        computed_hash: hex,
+        reference_hash: Some(original.computed_hash.clone()),
+        reference_encoding: (outcome == ReverifyOutcome::Unchanged).then_some(HashEncoding::Hex),
+        digests: BTreeMap::new(),
+        integrity_only: algo.is_integrity_only(),
+        manifest: None,
+        metadata,
+        parent_id: Some(original.id.clone()),
+        reverify: Some(outcome),
+        prev_hash: None,
+        status: match outcome {
+            ReverifyOutcome::Unchanged => VerificationStatus::Success,
+            ReverifyOutcome::Missing => VerificationStatus::Missing,
+            ReverifyOutcome::Changed | ReverifyOutcome::Corrupted => VerificationStatus::Failed,
+        },
+        timestamp: Utc::now(),
+    })
+}
+
+/// Re-verification of a manifest record, linked to it like a file's.
+fn reverify_manifest(
+    original: &VerificationRecord,
+    progress: &mut dyn FnMut(u64),
+    cancel: Option<&CancelToken>,
+) -> Result<VerificationRecord> {
+    let report = match manifest::verify_manifest_with_progress(&original.file_path, progress, cancel) {
+        Ok(report) => report,
+        Err(e) if e.is::<hashers::Cancelled>() => return Err(e),
+        Err(e) => return Ok(reverify_error(original, &e)),
+    };
+    let rec = report.history_record();
+    let outcome = if rec.status == VerificationStatus::Success { ReverifyOutcome::Unchanged } else { ReverifyOutcome::Changed };
+    Ok(VerificationRecord {
+        file_name: original.file_name.clone(),
+        reference_hash: Some(original.computed_hash.clone()),
+        parent_id: Some(original.id.clone()),
+        reverify: Some(outcome),
+        ..rec
+    })
+}
+
+/// Re-verification of `original` that could not read the file.
+fn reverify_error(original: &VerificationRecord, err: &anyhow::Error) -> VerificationRecord {
+    VerificationRecord {
+        file_name: original.file_name.clone(),
+        parent_id: Some(original.id.clone()),
+        ..error_record(&original.file_path, &original.algorithm, Some(&original.computed_hash), err)
+    }
+}
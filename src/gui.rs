@@ -1,19 +1,34 @@
 use iced::{
-    Element, Length, Task, Color, Alignment, Border,
+    event, window, Element, Event, Length, Subscription, Task, Color, Alignment, Border,
 };
 use iced::widget::{
-    Column, Row, Container, Text, Button, PickList, TextInput, Scrollable, Space, rule,
+    Column, Row, Container, Text, Button, PickList, TextInput, Scrollable, Space, Checkbox, ProgressBar, rule,
 };
 
-use crate::models::*;
-use crate::storage;
-use crate::utils;
+use verifile::audit::ChainReport;
+use verifile::detect;
+use verifile::export::{self, ExportFormat};
+use verifile::file_ops;
+use verifile::history::{self, HistoryPage, HistoryQuery, SortKey};
+use verifile::import;
+use verifile::hashers::{CancelToken, Cancelled};
+use verifile::manifest;
+use verifile::models::*;
+use verifile::progress::{self, Progress};
+use verifile::storage;
 use std::path::PathBuf;
-use uuid::Uuid;
+use std::sync::{Arc, Mutex};
 use anyhow::Result;
 use rfd::FileDialog;
+use futures::channel::mpsc;
+use std::time::{Duration, Instant};
 use tokio::task;
-use chrono::Utc;
+
+const WARNING_COLOR: Color = Color::from_rgb(0.9, 0.55, 0.1);
+
+/// Files dropped within this long of each other count as one drop, so a data
+/// file dropped together with its checksum file starts verifying at once.
+const DROP_BATCH_WINDOW: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum VerificationStep {
@@ -23,59 +38,255 @@ pub enum VerificationStep {
     Result,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Page {
+    Single,
+    Queue,
+    History,
+}
+
+/// Pick list entry for an optional filter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Choice<T> {
+    All,
+    Only(T),
+}
+
+impl<T> Choice<T> {
+    fn into_option(self) -> Option<T> {
+        match self {
+            Choice::All => None,
+            Choice::Only(t) => Some(t),
+        }
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Choice<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Choice::All => f.write_str("All"),
+            Choice::Only(t) => t.fmt(f),
+        }
+    }
+}
+
+/// Where a queued file is in its run.
+#[derive(Debug, Clone)]
+pub enum QueueState {
+    Pending,
+    Running(Progress, CancelToken),
+    Done(Box<VerificationRecord>),
+    Error(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct QueueItem {
+    id: usize,
+    path: PathBuf,
+    algorithm: Algorithm,
+    reference: String,
+    state: QueueState,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Theme {
     Light,
     Dark,
 }
 
+/// What importing a batch of logs from the history page added.
+#[derive(Debug, Clone)]
+pub struct LogImport {
+    added: usize,
+    // lines of the logs that were not understood
+    skipped_lines: usize,
+    // imported records that carry no digest and so cannot be re-verified
+    without_digest: usize,
+    // the whole history after the import
+    past: Vec<VerificationRecord>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     ChooseFile,
     FileChosen(Option<PathBuf>),
     AlgorithmSelected(Algorithm),
+    ExtraAlgorithmToggled(Algorithm, bool),
     PasteHashChanged(String),
     LoadHashFile,
-    HashFileLoaded(Option<String>),
+    HashFileLoaded(Option<(PathBuf, String)>),
     ProceedToHash,
     StartVerify,
+    VerifyProgress(u64),
+    CancelVerify,
+    VerifyCancelled,
+    RecordCancelledToggled(bool),
     VerifyComplete(Result<VerificationRecord, String>),
+    ChooseManifest,
+    ManifestChosen(Option<PathBuf>),
+    ManifestVerified(Result<manifest::ManifestReport, String>),
+    GenerateFormatSelected(manifest::ManifestFormat),
+    GenerateIncludeChanged(String),
+    GenerateExcludeChanged(String),
+    GenerateAbsoluteToggled(bool),
+    GenerateFromFiles,
+    GenerateFromFolder,
+    GenerateInputsChosen(Option<Vec<PathBuf>>),
+    GenerateTargetChosen(Vec<PathBuf>, Option<PathBuf>),
+    ManifestGenerated(Result<(PathBuf, usize), String>),
+    FileDropped(PathBuf),
     ResetVerification,
+    ShowPage(Page),
+    Reverify(String),
+    HistorySearchChanged(String),
+    HistoryStatusSelected(Choice<VerificationStatus>),
+    HistoryAlgorithmSelected(Choice<Algorithm>),
+    HistoryFromChanged(String),
+    HistoryToChanged(String),
+    HistorySortBy(SortKey),
+    HistoryPageSelected(usize),
+    VerifyHistoryChain,
+    ExportFormatSelected(ExportFormat),
+    ExportHistory,
+    ExportTargetChosen(Option<PathBuf>),
+    HistoryExported(Result<(PathBuf, usize), String>),
+    ImportLogs,
+    ImportLogsChosen(Option<Vec<PathBuf>>),
+    ImportChecksumFileChosen(Vec<PathBuf>, Option<PathBuf>),
+    LogsImported(Result<LogImport, String>),
+    HistoryChainVerified(Result<ChainReport, String>),
+    QueueAddFiles,
+    QueueFilesAdded(Option<Vec<PathBuf>>),
+    QueueAlgorithmSelected(usize, Algorithm),
+    QueueReferenceChanged(usize, String),
+    QueueRemove(usize),
+    QueueWorkersSelected(usize),
+    QueueStart,
+    QueueCancel,
+    QueueRetry,
+    QueueClear,
+    QueueProgress(usize, u64),
+    QueueItemFinished(usize, Option<Result<VerificationRecord, String>>),
     ToggleHistory,
     ToggleTheme,
+    DismissStorageWarning,
 }
 
 pub struct VeriFileApp {
     // UI state
     chosen_file: Option<PathBuf>,
     algorithm: Algorithm,
+    // additional digests computed in the same pass as `algorithm`
+    extra_algorithms: Vec<Algorithm>,
     paste_hash: String,
+    // algorithms that could have produced `paste_hash`
+    detected: Vec<Algorithm>,
+    detection_note: Option<String>,
+    // outcome of the last "Load Hash from File"
+    hash_file_note: Option<String>,
+    // checksum file dropped before its data file, applied once a file is chosen
+    pending_checksum: Option<(PathBuf, String)>,
+    // when the current drop batch started, and whether it held a data file / checksum file
+    drop_batch: Option<(Instant, bool, bool)>,
     status_message: String,
     current_step: VerificationStep,
     is_verifying: bool,
+    // bytes hashed so far by the running verification
+    progress: Option<Progress>,
+    // set while a cancellable verification is running
+    cancel: Option<CancelToken>,
+    // the history record being re-verified by the running verification
+    reverifying: Option<VerificationRecord>,
+    settings: Settings,
+    page: Page,
+    queue: Vec<QueueItem>,
+    next_queue_id: usize,
+    // maximum number of queued files hashed at once
+    queue_workers: usize,
+    // whether finished workers should pick up the next pending item
+    queue_running: bool,
     show_history: bool,
     last_result: Option<VerificationRecord>,
+    // per-entry results when the last run checked a whole manifest
+    manifest_report: Option<manifest::ManifestReport>,
+    // manifest generation settings (comma-separated globs)
+    gen_format: manifest::ManifestFormat,
+    gen_include: String,
+    gen_exclude: String,
+    gen_absolute: bool,
+    generate_note: Option<String>,
     theme: Theme,
 
-    // past verifications
+    // past verifications, and the history database they are saved to
+    // (None if it could not be opened); shared with background tasks
     past: Vec<VerificationRecord>,
+    history: Option<Arc<Mutex<storage::History>>>,
+    // problems reading or saving history and settings, shown until dismissed
+    storage_warning: Option<String>,
+    // history browser filters; the date bounds are kept as typed
+    history_query: HistoryQuery,
+    history_from: String,
+    history_to: String,
+    // outcome of the last "Verify Chain" run on the history page
+    history_chain: Option<Result<ChainReport, String>>,
+    export_format: ExportFormat,
+    history_note: Option<String>,
+    // current page of `past` under `history_query`
+    history_page: HistoryPage,
 }
 
 impl VeriFileApp {
     pub fn new() -> (Self, Task<Message>) {
-        let past = storage::load_all();
+        let (history, past, storage_warning) = match storage::load_all() {
+            Ok((history, loaded)) if loaded.warnings.is_empty() => (Some(history), loaded.records, None),
+            Ok((history, loaded)) => (Some(history), loaded.records, Some(loaded.warnings.join("\n"))),
+            Err(e) => (None, Vec::new(), Some(format!("Could not load history: {:#}", e))),
+        };
+        let history = history.map(|h| Arc::new(Mutex::new(h)));
+        let history_query = HistoryQuery::default();
+        let history_page = history::query(&past, &history_query);
         (
             VeriFileApp {
                 chosen_file: None,
                 algorithm: Algorithm::Blake3,
+                extra_algorithms: Vec::new(),
                 paste_hash: String::new(),
+                detected: Vec::new(),
+                detection_note: None,
+                hash_file_note: None,
+                pending_checksum: None,
+                drop_batch: None,
                 status_message: String::new(),
                 current_step: VerificationStep::UploadFile,
                 is_verifying: false,
+                progress: None,
+                cancel: None,
+                reverifying: None,
+                settings: storage::load_settings(),
+                page: Page::Single,
+                queue: Vec::new(),
+                next_queue_id: 0,
+                queue_workers: std::thread::available_parallelism().map_or(2, |n| n.get().min(4)),
+                queue_running: false,
                 show_history: false,
                 theme: Theme::Light,
                 last_result: None,
+                manifest_report: None,
+                gen_format: manifest::ManifestFormat::Gnu,
+                gen_include: String::new(),
+                gen_exclude: String::new(),
+                gen_absolute: false,
+                generate_note: None,
                 past,
+                history,
+                storage_warning,
+                history_query,
+                history_from: String::new(),
+                history_to: String::new(),
+                history_chain: None,
+                export_format: ExportFormat::Html,
+                history_note: None,
+                history_page,
             },
             Task::none(),
         )
@@ -86,110 +297,570 @@ impl VeriFileApp {
             Message::ChooseFile => {
                 return Task::perform(async {
                     FileDialog::new().set_directory(".").pick_file()
-                }, |res| Message::FileChosen(res));
+                }, Message::FileChosen);
             }
             Message::FileChosen(Some(path)) => {
                 self.chosen_file = Some(path);
                 self.current_step = VerificationStep::UploadFile;
+                if let Some((p, txt)) = self.pending_checksum.take() {
+                    self.load_checksum_file(&p, &txt);
+                }
             }
             Message::FileChosen(None) => { /* cancelled */ }
             Message::AlgorithmSelected(a) => {
+                self.extra_algorithms.retain(|x| x != &a);
                 self.algorithm = a;
             }
+            Message::ExtraAlgorithmToggled(a, checked) => {
+                self.extra_algorithms.retain(|x| x != &a);
+                if checked {
+                    self.extra_algorithms.push(a);
+                    self.extra_algorithms.sort();
+                }
+            }
             Message::ProceedToHash => {
                 if self.chosen_file.is_some() {
                     self.current_step = VerificationStep::UploadHash;
                 }
             }
             Message::PasteHashChanged(s) => {
-                self.paste_hash = s;
+                self.set_reference_hash(s);
             }
             Message::LoadHashFile => {
                 return Task::perform(async {
-                    FileDialog::new().set_directory(".").add_filter("text", &["txt", "hash", "md"]).pick_file()
+                    FileDialog::new()
+                        .set_directory(".")
+                        .add_filter("Checksum files", &["txt", "hash", "md", "sum", "sums", "sha1", "sha256", "sha512", "md5", "b2", "b3"])
+                        .add_filter("All files", &["*"])
+                        .pick_file()
                 }, |res| {
-                    if let Some(p) = res {
-                        let txt = std::fs::read_to_string(p).ok();
-                        Message::HashFileLoaded(txt)
-                    } else {
-                        Message::HashFileLoaded(None)
-                    }
+                    Message::HashFileLoaded(res.and_then(|p| {
+                        let txt = std::fs::read_to_string(&p).ok()?;
+                        Some((p, txt))
+                    }))
                 });
             }
             Message::HashFileLoaded(opt) => {
-                if let Some(txt) = opt {
-                    if let Some(h) = utils::parse_first_hash_from_text(&txt) {
-                        self.paste_hash = h;
-                    }
+                if let Some((path, txt)) = opt {
+                    self.load_checksum_file(&path, &txt);
                 }
             }
             Message::StartVerify => {
                 if let Some(path) = self.chosen_file.clone() {
-                    println!("Starting verification for: {:?}", path);
                     self.status_message = "Computing hash...".to_string();
                     self.current_step = VerificationStep::Verifying;
                     self.is_verifying = true;
                     let algo = self.algorithm.clone();
+                    let extras = self.extra_algorithms.clone();
                     let ref_hash = if self.paste_hash.trim().is_empty() { None } else { Some(self.paste_hash.clone()) };
-                    return Task::perform(async move {
-                        let computed: Result<(String, PathBuf, Algorithm), anyhow::Error> = task::spawn_blocking(move || {
-                            let hex = crate::file_ops::compute_file_hash(&path, &algo)?;
-                            Ok((hex, path, algo))
-                        }).await.unwrap();
-                        match computed {
-                            Ok((hex, path, algo)) => {
-                                let status = if let Some(rh) = &ref_hash {
-                                    if rh.trim().eq_ignore_ascii_case(&hex) { VerificationStatus::Success } else { VerificationStatus::Failed }
-                                } else {
-                                    VerificationStatus::Success
-                                };
-                                let rec = VerificationRecord {
-                                    id: Uuid::new_v4().to_string(),
-                                    file_name: path.file_name().and_then(|s| s.to_str()).unwrap_or("file").to_string(),
-                                    file_path: path,
-                                    algorithm: algo,
-                                    computed_hash: hex,
-                                    reference_hash: ref_hash,
-                                    status,
-                                    timestamp: Utc::now(),
-                                };
-                                Ok(rec)
-                            },
-                            Err(e) => Err(format!("Hash compute error: {:?}", e)),
-                        }
-                    }, |res| Message::VerifyComplete(res));
+                    let total = std::fs::metadata(&path).ok().map(|m| m.len());
+                    self.progress = Some(Progress::new(total));
+                    let cancel = CancelToken::default();
+                    self.cancel = Some(cancel.clone());
+                    return run_in_background(
+                        move |progress, cancel| {
+                            verify_or_record(&path, &algo, &extras, ref_hash.as_deref(), progress, cancel)
+                        },
+                        cancel,
+                        Message::VerifyProgress,
+                        single_file_done,
+                    );
+                }
+            }
+            Message::VerifyProgress(bytes) => {
+                if let Some(p) = self.progress.as_mut() {
+                    p.update(bytes);
+                }
+            }
+            Message::CancelVerify => {
+                if let Some(cancel) = &self.cancel {
+                    cancel.cancel();
+                    self.status_message = "Cancelling...".to_string();
+                }
+            }
+            Message::VerifyCancelled => {
+                self.is_verifying = false;
+                self.progress = None;
+                self.cancel = None;
+                self.current_step = VerificationStep::Result;
+                self.status_message = "Verification cancelled".to_string();
+                self.detection_note = None;
+                self.manifest_report = None;
+                let rec = match (self.reverifying.take(), &self.chosen_file) {
+                    (Some(original), _) => Some(file_ops::cancelled_reverify_record(&original)),
+                    (None, Some(path)) => Some(file_ops::cancelled_record(path, &self.algorithm, Some(&self.paste_hash))),
+                    (None, None) => None,
+                };
+                if let Some(rec) = rec {
+                    if self.settings.record_cancelled {
+                        self.record(rec.clone());
+                    }
+                    self.last_result = Some(rec);
+                }
+            }
+            Message::RecordCancelledToggled(on) => {
+                self.settings.record_cancelled = on;
+                if let Err(e) = storage::save_settings(&self.settings) {
+                    self.storage_warning = Some(format!("Could not save settings: {:#}", e));
                 }
             }
             Message::VerifyComplete(result) => {
                 self.is_verifying = false;
+                self.reverifying = None;
+                self.progress = None;
+                self.cancel = None;
                 self.current_step = VerificationStep::Result;
                 match result {
                     Ok(rec) => {
-                        println!("Verification complete: {:?}", rec.status);
-                        let status_msg = match rec.status {
-                            VerificationStatus::Success => "✓ Verification successful!",
-                            VerificationStatus::Failed => "✗ Verification failed - hash mismatch!",
-                            VerificationStatus::InProgress => "In progress...",
+                        let status_msg = match (rec.reverify, &rec.status) {
+                            (Some(ReverifyOutcome::Unchanged), _) => "✓ File unchanged since it was last verified".into(),
+                            (Some(ReverifyOutcome::Changed), _) => "✗ File has changed since it was last verified!".into(),
+                            (Some(ReverifyOutcome::Corrupted), _) => "✗ Content changed without the file being modified - possible corruption!".into(),
+                            (Some(ReverifyOutcome::Missing), _) => "✗ File no longer exists at its recorded path".into(),
+                            (None, VerificationStatus::Success) => "✓ Verification successful!".into(),
+                            (None, VerificationStatus::Failed) => "✗ Verification failed - hash mismatch!".into(),
+                            (None, VerificationStatus::Unverified) => "Hash computed - no reference hash to compare against".into(),
+                            (None, VerificationStatus::Missing) => "✗ File not found".into(),
+                            (None, VerificationStatus::Error { message, .. }) => format!("✗ Could not read the file: {}", message),
+                            (None, VerificationStatus::InProgress) => "In progress...".into(),
+                            (None, VerificationStatus::Cancelled) => "Verification cancelled".into(),
+                        };
+                        self.status_message = status_msg;
+                        self.detection_note = if self.detected.len() > 1 && rec.reference_hash.is_some() {
+                            match rec.status {
+                                VerificationStatus::Success => Some(format!(
+                                    "Reference matched {} (auto-detected among {} candidates)",
+                                    rec.algorithm.name(),
+                                    self.detected.len()
+                                )),
+                                _ => Some(format!(
+                                    "No candidate algorithm matched: {}",
+                                    self.detected.iter().map(|a| a.name()).collect::<Vec<_>>().join(", ")
+                                )),
+                            }
+                        } else {
+                            None
                         };
-                        self.status_message = status_msg.to_string();
                         self.last_result = Some(rec.clone());
-                        self.past.insert(0, rec.clone());
-                        let _ = storage::save_all(&self.past);
+                        self.manifest_report = None;
+                        self.record(rec.clone());
+                    }
+                    Err(e) => {
+                        self.status_message = format!("Error: {}", e);
+                        self.last_result = None;
+                    }
+                }
+            }
+            Message::ChooseManifest => {
+                return Task::perform(async {
+                    FileDialog::new()
+                        .set_directory(".")
+                        .add_filter("Checksum files", &["txt", "sum", "sums", "sha1", "sha256", "sha512", "md5", "b2", "b3"])
+                        .add_filter("All files", &["*"])
+                        .pick_file()
+                }, Message::ManifestChosen);
+            }
+            Message::ManifestChosen(Some(path)) => {
+                self.status_message = format!("Checking entries of {}...", path.display());
+                self.current_step = VerificationStep::Verifying;
+                self.is_verifying = true;
+                return Task::perform(async move {
+                    task::spawn_blocking(move || manifest::verify_manifest(&path))
+                        .await
+                        .unwrap()
+                        .map_err(|e| format!("Manifest error: {:?}", e))
+                }, Message::ManifestVerified);
+            }
+            Message::ManifestChosen(None) => { /* cancelled */ }
+            Message::ManifestVerified(result) => {
+                self.is_verifying = false;
+                self.current_step = VerificationStep::Result;
+                self.detection_note = None;
+                match result {
+                    Ok(report) => {
+                        let rec = report.history_record();
+                        let summary = report.summary();
+                        self.status_message = format!(
+                            "{} OK, {} FAILED, {} MISSING",
+                            summary.ok, summary.failed, summary.missing
+                        );
+                        self.last_result = Some(rec.clone());
+                        self.manifest_report = Some(report);
+                        self.record(rec);
                     }
                     Err(e) => {
-                        println!("Verification error: {:?}", e);
                         self.status_message = format!("Error: {}", e);
                         self.last_result = None;
+                        self.manifest_report = None;
                     }
                 }
             }
+            Message::GenerateFormatSelected(f) => {
+                self.gen_format = f;
+            }
+            Message::GenerateIncludeChanged(s) => {
+                self.gen_include = s;
+            }
+            Message::GenerateExcludeChanged(s) => {
+                self.gen_exclude = s;
+            }
+            Message::GenerateAbsoluteToggled(b) => {
+                self.gen_absolute = b;
+            }
+            Message::GenerateFromFiles => {
+                return Task::perform(async {
+                    FileDialog::new().set_directory(".").pick_files()
+                }, Message::GenerateInputsChosen);
+            }
+            Message::GenerateFromFolder => {
+                return Task::perform(async {
+                    FileDialog::new().set_directory(".").pick_folder().map(|p| vec![p])
+                }, Message::GenerateInputsChosen);
+            }
+            Message::GenerateInputsChosen(Some(inputs)) => {
+                let start_dir = inputs
+                    .first()
+                    .map(|p| if p.is_dir() { p.clone() } else { p.parent().map(PathBuf::from).unwrap_or_default() })
+                    .unwrap_or_else(|| PathBuf::from("."));
+                let file_name = self.gen_format.default_file_name(&self.generate_algorithm());
+                return Task::perform(async move {
+                    let target = FileDialog::new().set_directory(&start_dir).set_file_name(file_name).save_file();
+                    (inputs, target)
+                }, |(inputs, target)| Message::GenerateTargetChosen(inputs, target));
+            }
+            Message::GenerateInputsChosen(None) => { /* cancelled */ }
+            Message::GenerateTargetChosen(_, None) => { /* cancelled */ }
+            Message::GenerateTargetChosen(inputs, Some(target)) => {
+                let split = |s: &str| s.split(',').map(str::trim).filter(|p| !p.is_empty()).map(String::from).collect::<Vec<_>>();
+                let opts = manifest::GenerateOptions {
+                    algorithm: self.generate_algorithm(),
+                    include: split(&self.gen_include),
+                    exclude: split(&self.gen_exclude),
+                    absolute_paths: self.gen_absolute,
+                };
+                let format = self.gen_format;
+                self.generate_note = Some("Generating manifest...".to_string());
+                return Task::perform(async move {
+                    task::spawn_blocking(move || -> Result<(PathBuf, usize)> {
+                        let lines = manifest::generate_manifest(&inputs, &opts)?;
+                        let text = manifest::render_manifest(&lines, &opts.algorithm, format)?;
+                        std::fs::write(&target, text)?;
+                        Ok((target, lines.len()))
+                    })
+                    .await
+                    .unwrap()
+                    .map_err(|e| format!("{:#}", e))
+                }, Message::ManifestGenerated);
+            }
+            Message::ManifestGenerated(result) => {
+                self.generate_note = Some(match result {
+                    Ok((path, n)) => format!("Wrote {} entries to {}", n, path.display()),
+                    Err(e) => format!("Generation failed: {}", e),
+                });
+            }
+            Message::FileDropped(path) => {
+                return self.file_dropped(path);
+            }
             Message::ResetVerification => {
+                self.manifest_report = None;
+                self.pending_checksum = None;
                 self.chosen_file = None;
                 self.paste_hash.clear();
+                self.hash_file_note = None;
+                self.detected.clear();
+                self.detection_note = None;
                 self.status_message.clear();
                 self.current_step = VerificationStep::UploadFile;
                 self.last_result = None;
             }
+            Message::ShowPage(page) => {
+                self.page = page;
+            }
+            Message::Reverify(id) => {
+                if self.is_verifying {
+                    return Task::none();
+                }
+                let Some(original) = self.past.iter().find(|r| r.id == id).cloned() else {
+                    return Task::none();
+                };
+                // Mirror the original run in the single-file flow so the
+                // progress and result screens match it.
+                self.page = Page::Single;
+                self.chosen_file = Some(original.file_path.clone());
+                self.algorithm = original.algorithm.clone();
+                self.extra_algorithms.retain(|a| a != &original.algorithm);
+                self.paste_hash = original.computed_hash.clone();
+                self.detected.clear();
+                self.detection_note = None;
+                self.manifest_report = None;
+                self.status_message = if original.manifest.is_some() {
+                    format!("Re-checking the files listed in {}...", original.file_name)
+                } else {
+                    format!("Re-hashing {} with {}...", original.file_name, original.algorithm.name())
+                };
+                self.current_step = VerificationStep::Verifying;
+                self.is_verifying = true;
+                let total = if original.manifest.is_some() {
+                    manifest::listed_size(&original.file_path)
+                } else {
+                    std::fs::metadata(&original.file_path).ok().map(|m| m.len())
+                };
+                self.progress = Some(Progress::new(total));
+                let cancel = CancelToken::default();
+                self.cancel = Some(cancel.clone());
+                self.reverifying = Some(original.clone());
+                return run_in_background(
+                    move |progress, cancel| file_ops::reverify_with_progress(&original, progress, Some(cancel)),
+                    cancel,
+                    Message::VerifyProgress,
+                    single_file_done,
+                );
+            }
+            Message::HistorySearchChanged(text) => {
+                self.history_query.text = text;
+                self.refresh_history(true);
+            }
+            Message::HistoryStatusSelected(choice) => {
+                self.history_query.status = choice.into_option();
+                self.refresh_history(true);
+            }
+            Message::HistoryAlgorithmSelected(choice) => {
+                self.history_query.algorithm = choice.into_option();
+                self.refresh_history(true);
+            }
+            Message::HistoryFromChanged(text) => {
+                self.history_query.from = chrono::NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok();
+                self.history_from = text;
+                self.refresh_history(true);
+            }
+            Message::HistoryToChanged(text) => {
+                self.history_query.to = chrono::NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok();
+                self.history_to = text;
+                self.refresh_history(true);
+            }
+            Message::HistorySortBy(key) => {
+                if self.history_query.sort == key {
+                    self.history_query.descending = !self.history_query.descending;
+                } else {
+                    self.history_query.sort = key;
+                    self.history_query.descending = key == SortKey::Date;
+                }
+                self.refresh_history(true);
+            }
+            Message::HistoryPageSelected(page) => {
+                self.history_query.page = page;
+                self.refresh_history(false);
+            }
+            Message::VerifyHistoryChain => {
+                self.history_chain = None;
+                let Some(history) = self.history.clone() else {
+                    self.history_chain = Some(Err("the history database is not open".to_string()));
+                    return Task::none();
+                };
+                return Task::perform(async move {
+                    task::spawn_blocking(move || history.lock().unwrap().verify_chain())
+                        .await
+                        .unwrap()
+                        .map_err(|e| format!("{:#}", e))
+                }, Message::HistoryChainVerified);
+            }
+            Message::HistoryChainVerified(result) => {
+                self.history_chain = Some(result);
+            }
+            Message::ExportFormatSelected(f) => {
+                self.export_format = f;
+            }
+            Message::ExportHistory => {
+                let file_name = format!("verifile-history.{}", self.export_format.extension());
+                return Task::perform(async move {
+                    FileDialog::new().set_directory(".").set_file_name(file_name).save_file()
+                }, Message::ExportTargetChosen);
+            }
+            Message::ExportTargetChosen(None) => { /* cancelled */ }
+            Message::ExportTargetChosen(Some(target)) => {
+                // Exports every match of the current filters, not just this page.
+                let count = self.history_page.total;
+                let result = export::export(&self.past, &self.history_query, self.export_format);
+                self.history_note = Some("Exporting...".to_string());
+                return Task::perform(async move {
+                    task::spawn_blocking(move || -> Result<(PathBuf, usize)> {
+                        std::fs::write(&target, result?)?;
+                        Ok((target, count))
+                    })
+                    .await
+                    .unwrap()
+                    .map_err(|e| format!("{:#}", e))
+                }, Message::HistoryExported);
+            }
+            Message::HistoryExported(result) => {
+                self.history_note = Some(match result {
+                    Ok((path, n)) => format!("Exported {} records to {}", n, path.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                });
+            }
+            Message::ImportLogs => {
+                return Task::perform(async {
+                    FileDialog::new()
+                        .set_directory(".")
+                        .add_filter("Logs", &["log", "txt", "sfv", "hashdeep", "sum", "sums"])
+                        .add_filter("All files", &["*"])
+                        .pick_files()
+                }, Message::ImportLogsChosen);
+            }
+            Message::ImportLogsChosen(None) => { /* cancelled */ }
+            Message::ImportLogsChosen(Some(logs)) => {
+                // `-c` output has verdicts but no digests; ask for the
+                // checksum file it checked, as `verifile import --manifest`.
+                let check_logs = logs.iter().any(|l| std::fs::read_to_string(l).is_ok_and(|t| import::is_check_log(&t)));
+                if !check_logs {
+                    return Task::done(Message::ImportChecksumFileChosen(logs, None));
+                }
+                let start_dir = logs[0].parent().map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
+                return Task::perform(async move {
+                    let checksums = FileDialog::new()
+                        .set_title("Checksum file the logs checked (cancel to import without digests)")
+                        .set_directory(&start_dir)
+                        .add_filter("Checksum files", &["txt", "sum", "sums", "sha1", "sha256", "sha512", "md5", "b2", "b3"])
+                        .add_filter("All files", &["*"])
+                        .pick_file();
+                    (logs, checksums)
+                }, |(logs, checksums)| Message::ImportChecksumFileChosen(logs, checksums));
+            }
+            Message::ImportChecksumFileChosen(logs, checksums) => {
+                let Some(history) = self.history.clone() else {
+                    self.history_note = Some("Import failed: the history database is not open".to_string());
+                    return Task::none();
+                };
+                self.history_note = Some("Importing...".to_string());
+                return Task::perform(async move {
+                    task::spawn_blocking(move || -> Result<LogImport> {
+                        let manifest = match &checksums {
+                            Some(path) => {
+                                let text = std::fs::read_to_string(path)?;
+                                Some(manifest::parse_checksum_text(&text, manifest::algorithm_hint_from_path(path).as_ref()))
+                            }
+                            None => None,
+                        };
+                        let store = history.lock().unwrap();
+                        let mut summary = LogImport { added: 0, skipped_lines: 0, without_digest: 0, past: Vec::new() };
+                        for log in &logs {
+                            let mut opts = import::ImportOptions::for_log(log);
+                            opts.manifest = manifest.clone();
+                            let parsed = import::import_file(log, None, opts)?;
+                            if let Some(n) = store.import_log_once(log, &parsed.records)? {
+                                summary.added += n;
+                                summary.skipped_lines += parsed.diagnostics.len();
+                                summary.without_digest += parsed.records.iter().filter(|r| r.computed_hash.is_empty()).count();
+                            }
+                        }
+                        summary.past = store.load_all()?;
+                        Ok(summary)
+                    })
+                    .await
+                    .unwrap()
+                    .map_err(|e| format!("{:#}", e))
+                }, Message::LogsImported);
+            }
+            Message::LogsImported(result) => {
+                match result {
+                    Ok(summary) => {
+                        let mut note = format!("Imported {} records", summary.added);
+                        if summary.without_digest > 0 {
+                            note.push_str(&format!("; {} have no digest and cannot be re-verified", summary.without_digest));
+                        }
+                        if summary.skipped_lines > 0 {
+                            note.push_str(&format!("; skipped {} unrecognized lines", summary.skipped_lines));
+                        }
+                        self.history_note = Some(note);
+                        self.past = summary.past;
+                        self.refresh_history(false);
+                    }
+                    Err(e) => self.history_note = Some(format!("Import failed: {}", e)),
+                }
+            }
+            Message::QueueAddFiles => {
+                return Task::perform(async {
+                    FileDialog::new()
+                        .set_directory(".")
+                        .pick_files()
+                }, Message::QueueFilesAdded);
+            }
+            Message::QueueFilesAdded(Some(paths)) => {
+                for path in paths {
+                    self.queue_push(path, String::new());
+                }
+            }
+            Message::QueueFilesAdded(None) => { /* cancelled */ }
+            Message::QueueAlgorithmSelected(id, algo) => {
+                if let Some(item) = self.queue_item_mut(id).filter(|i| !matches!(i.state, QueueState::Running(..))) {
+                    item.algorithm = algo;
+                }
+            }
+            Message::QueueReferenceChanged(id, reference) => {
+                if let Some(item) = self.queue_item_mut(id).filter(|i| !matches!(i.state, QueueState::Running(..))) {
+                    if let [only] = detect::candidate_algorithms(&reference).as_slice() {
+                        item.algorithm = only.clone();
+                    }
+                    item.reference = reference;
+                }
+            }
+            Message::QueueRemove(id) => {
+                self.queue.retain(|i| i.id != id || matches!(i.state, QueueState::Running(..)));
+            }
+            Message::QueueWorkersSelected(n) => {
+                self.queue_workers = n;
+                return self.queue_schedule();
+            }
+            Message::QueueStart => {
+                self.queue_running = true;
+                return self.queue_schedule();
+            }
+            Message::QueueCancel => {
+                self.queue_running = false;
+                for item in &self.queue {
+                    if let QueueState::Running(_, cancel) = &item.state {
+                        cancel.cancel();
+                    }
+                }
+            }
+            Message::QueueRetry => {
+                for item in self.queue.iter_mut() {
+                    if !matches!(item.state, QueueState::Running(..)) {
+                        item.state = QueueState::Pending;
+                    }
+                }
+                self.queue_running = true;
+                return self.queue_schedule();
+            }
+            Message::QueueClear => {
+                self.queue.retain(|i| matches!(i.state, QueueState::Running(..)));
+            }
+            Message::QueueProgress(id, bytes) => {
+                if let Some(QueueItem { state: QueueState::Running(p, _), .. }) = self.queue_item_mut(id) {
+                    p.update(bytes);
+                }
+            }
+            Message::QueueItemFinished(id, result) => {
+                let record_cancelled = self.settings.record_cancelled;
+                let Some(item) = self.queue_item_mut(id) else {
+                    return self.queue_schedule();
+                };
+                let rec = match result {
+                    Some(Ok(rec)) => Some(rec),
+                    Some(Err(e)) => {
+                        item.state = QueueState::Error(e);
+                        None
+                    }
+                    None => Some(file_ops::cancelled_record(&item.path, &item.algorithm, Some(&item.reference))),
+                };
+                if let Some(rec) = rec {
+                    item.state = QueueState::Done(Box::new(rec.clone()));
+                    if rec.status != VerificationStatus::Cancelled || record_cancelled {
+                        self.record(rec);
+                    }
+                }
+                return self.queue_schedule();
+            }
             Message::ToggleHistory => {
                 self.show_history = !self.show_history;
             }
@@ -199,20 +870,240 @@ impl VeriFileApp {
                     Theme::Dark => Theme::Light,
                 };
             }
+            Message::DismissStorageWarning => {
+                self.storage_warning = None;
+            }
         }
         Task::none()
     }
 
+    /// A data file selects it, a checksum file becomes the reference; both in
+    /// one drop start verification straight away. On the queue page data
+    /// files are queued and checksum files fill in their references.
+    fn file_dropped(&mut self, path: PathBuf) -> Task<Message> {
+        let is_checksum = manifest::looks_like_checksum_file(&path);
+
+        if self.page == Page::Queue {
+            if !is_checksum {
+                self.queue_push(path, String::new());
+            } else if let Ok(txt) = std::fs::read_to_string(&path) {
+                self.queue_apply_checksums(&path, &txt);
+            }
+            return Task::none();
+        }
+        if self.is_verifying {
+            return Task::none();
+        }
+
+        let (started, mut had_data, mut had_checksum) = match self.drop_batch {
+            Some(batch) if batch.0.elapsed() < DROP_BATCH_WINDOW => batch,
+            _ => (Instant::now(), false, false),
+        };
+        if is_checksum {
+            match std::fs::read_to_string(&path) {
+                Ok(txt) => self.pending_checksum = Some((path, txt)),
+                Err(e) => {
+                    self.hash_file_note = Some(format!("Could not read {}: {}", path.display(), e));
+                    return Task::none();
+                }
+            }
+            had_checksum = true;
+        } else {
+            self.chosen_file = Some(path);
+            self.manifest_report = None;
+            self.last_result = None;
+            had_data = true;
+        }
+        self.drop_batch = Some((started, had_data, had_checksum));
+
+        if self.chosen_file.is_none() {
+            self.current_step = VerificationStep::UploadFile;
+            return Task::none();
+        }
+        if let Some((p, txt)) = self.pending_checksum.take() {
+            self.load_checksum_file(&p, &txt);
+        }
+        if had_data && had_checksum && !self.paste_hash.trim().is_empty() {
+            self.drop_batch = None;
+            return self.update(Message::StartVerify);
+        }
+        self.current_step = if had_checksum { VerificationStep::UploadHash } else { VerificationStep::UploadFile };
+        Task::none()
+    }
+
+    /// Fill empty queue references from a checksum file, matching entries by
+    /// file name.
+    fn queue_apply_checksums(&mut self, path: &std::path::Path, txt: &str) {
+        let hint = manifest::algorithm_hint_from_path(path);
+        let parsed = manifest::parse_checksum_text(txt, hint.as_ref());
+        for item in self.queue.iter_mut().filter(|i| matches!(i.state, QueueState::Pending) && i.reference.trim().is_empty()) {
+            let name = item.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let entry = parsed.entries.iter().find(|e| {
+                e.file_name
+                    .as_deref()
+                    .and_then(|n| std::path::Path::new(n).file_name())
+                    .is_some_and(|n| n.to_string_lossy() == name)
+            });
+            if let Some(entry) = entry {
+                item.reference = entry.digest.clone();
+                let candidates = detect::candidate_algorithms(&entry.digest);
+                match (&entry.algorithm, candidates.as_slice()) {
+                    (Some(algo), _) | (None, [algo]) => item.algorithm = algo.clone(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Add a record to the front of the history and persist it.
+    fn record(&mut self, rec: VerificationRecord) {
+        let saved = match &self.history {
+            Some(history) => history.lock().unwrap().insert(&rec),
+            None => Err(anyhow::anyhow!("the history database is not open")),
+        };
+        if let Err(e) = saved {
+            self.storage_warning = Some(format!("Could not save this result to history: {:#}", e));
+        }
+        self.past.insert(0, rec);
+        self.refresh_history(false);
+    }
+
+    /// Re-run the history query, going back to the first page when the
+    /// filters changed.
+    fn refresh_history(&mut self, filters_changed: bool) {
+        if filters_changed {
+            self.history_query.page = 0;
+        }
+        self.history_page = history::query(&self.past, &self.history_query);
+    }
+
+    fn queue_item_mut(&mut self, id: usize) -> Option<&mut QueueItem> {
+        self.queue.iter_mut().find(|i| i.id == id)
+    }
+
+    fn queue_push(&mut self, path: PathBuf, reference: String) {
+        let algorithm = match detect::candidate_algorithms(&reference).as_slice() {
+            [only] => only.clone(),
+            _ => self.algorithm.clone(),
+        };
+        self.queue.push(QueueItem { id: self.next_queue_id, path, algorithm, reference, state: QueueState::Pending });
+        self.next_queue_id += 1;
+    }
+
+    /// Start pending queue items until `queue_workers` are running.
+    fn queue_schedule(&mut self) -> Task<Message> {
+        if !self.queue_running {
+            return Task::none();
+        }
+        let mut running = self.queue.iter().filter(|i| matches!(i.state, QueueState::Running(..))).count();
+        let mut tasks = Vec::new();
+        for item in self.queue.iter_mut() {
+            if running >= self.queue_workers {
+                break;
+            }
+            if !matches!(item.state, QueueState::Pending) {
+                continue;
+            }
+            let total = std::fs::metadata(&item.path).ok().map(|m| m.len());
+            let cancel = CancelToken::default();
+            item.state = QueueState::Running(Progress::new(total), cancel.clone());
+            running += 1;
+            let id = item.id;
+            let reference = Some(item.reference.clone()).filter(|r| !r.trim().is_empty());
+            let (path, algo) = (item.path.clone(), item.algorithm.clone());
+            tasks.push(run_in_background(
+                move |progress, cancel| {
+                    verify_or_record(&path, &algo, &[], reference.as_deref(), progress, cancel)
+                },
+                cancel,
+                move |bytes| Message::QueueProgress(id, bytes),
+                move |result| Message::QueueItemFinished(id, result),
+            ));
+        }
+        if running == 0 {
+            self.queue_running = false;
+        }
+        Task::batch(tasks)
+    }
+
+    /// Store the reference hash and infer candidate algorithms from it,
+    /// switching the selected algorithm when only one fits.
+    fn set_reference_hash(&mut self, s: String) {
+        self.detected = detect::candidate_algorithms(&s);
+        if let [only] = self.detected.as_slice() {
+            if only != &self.algorithm {
+                self.extra_algorithms.retain(|x| x != only);
+                self.algorithm = only.clone();
+            }
+        }
+        self.paste_hash = s;
+    }
+
+    /// `b3sum` manifests are BLAKE3 by definition; other formats use the
+    /// algorithm selected in the sidebar.
+    fn generate_algorithm(&self) -> Algorithm {
+        if self.gen_format == manifest::ManifestFormat::B3sum {
+            Algorithm::Blake3
+        } else {
+            self.algorithm.clone()
+        }
+    }
+
+    /// Pick the entry for the chosen file out of a checksum file and use it
+    /// as the reference hash.
+    fn load_checksum_file(&mut self, path: &std::path::Path, txt: &str) {
+        let hint = manifest::algorithm_hint_from_path(path);
+        let parsed = manifest::parse_checksum_text(txt, hint.as_ref());
+        let list_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("checksum file");
+        let target = self
+            .chosen_file
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let Some(entry) = parsed.entry_for(&target).cloned() else {
+            self.hash_file_note = Some(match parsed.diagnostics.first() {
+                Some(d) if parsed.entries.is_empty() => format!("{}: line {}: {}", list_name, d.line, d.message),
+                _ => format!("No entry for {} in {} ({} entries)", target, list_name, parsed.entries.len()),
+            });
+            return;
+        };
+        self.set_reference_hash(entry.digest.clone());
+        if let Some(algo) = entry.algorithm {
+            self.extra_algorithms.retain(|x| x != &algo);
+            self.detected = vec![algo.clone()];
+            self.algorithm = algo;
+        }
+        let mut note = format!("Loaded {} line {}", list_name, entry.line);
+        if let Some(name) = &entry.file_name {
+            note.push_str(&format!(" ({})", name));
+        }
+        if !parsed.diagnostics.is_empty() {
+            note.push_str(&format!(", {} unparsable line(s) skipped", parsed.diagnostics.len()));
+        }
+        self.hash_file_note = Some(note);
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        event::listen_with(|event, _status, _window| match event {
+            Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+            _ => None,
+        })
+    }
+
     pub fn view(&self) -> Element<'_, Message> {
         // Sidebar
         let sidebar = self.view_sidebar();
         
         // Main content based on current step
-        let main_content = match self.current_step {
-            VerificationStep::UploadFile => self.view_upload_file(),
-            VerificationStep::UploadHash => self.view_upload_hash(),
-            VerificationStep::Verifying => self.view_verifying(),
-            VerificationStep::Result => self.view_result(),
+        let main_content = match (self.page, &self.current_step) {
+            (Page::Queue, _) => self.view_queue(),
+            (Page::History, _) => self.view_history(),
+            (Page::Single, VerificationStep::UploadFile) => self.view_upload_file(),
+            (Page::Single, VerificationStep::UploadHash) => self.view_upload_hash(),
+            (Page::Single, VerificationStep::Verifying) => self.view_verifying(),
+            (Page::Single, VerificationStep::Result) => self.view_result(),
         };
 
         // Layout
@@ -274,7 +1165,7 @@ impl VeriFileApp {
         .width(Length::Fill);
 
         let tertiary_text = self.tertiary_text_color();
-        let algo_desc = Text::new(self.get_algorithm_description())
+        let algo_desc = Text::new(format!("{} ({}-bit)", self.algorithm.description(), self.algorithm.output_len() * 8))
             .size(12)
             .style(move |_theme| {
                 iced::widget::text::Style {
@@ -282,6 +1173,60 @@ impl VeriFileApp {
                 }
             });
 
+        let integrity_warning = if self.algorithm.is_integrity_only() {
+            Some(
+                Text::new("Integrity-only checksum: not tamper-resistant")
+                    .size(12)
+                    .style(|_theme| {
+                        iced::widget::text::Style {
+                            color: Some(WARNING_COLOR),
+                        }
+                    })
+            )
+        } else {
+            None
+        };
+
+        let extras_label = Text::new("Also Compute")
+            .size(14)
+            .style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(text_color),
+                }
+            });
+        let mut extras_list = Column::new().spacing(6);
+        for a in Algorithm::all().into_iter().filter(|a| a != &self.algorithm) {
+            let checked = self.extra_algorithms.contains(&a);
+            extras_list = extras_list.push(
+                Checkbox::new(a.name(), checked)
+                    .size(14)
+                    .text_size(12)
+                    .on_toggle(move |c| Message::ExtraAlgorithmToggled(a.clone(), c))
+            );
+        }
+        let extras_scrollable = Scrollable::new(extras_list)
+            .height(Length::Fixed(110.0));
+
+        let record_cancelled = Checkbox::new("Record cancelled runs in history", self.settings.record_cancelled)
+            .size(14)
+            .text_size(12)
+            .on_toggle(Message::RecordCancelledToggled);
+
+        let storage_warning = self.storage_warning.as_ref().map(|w| {
+            Column::new()
+                .spacing(6)
+                .push(Text::new(w).size(12).style(|_theme| {
+                    iced::widget::text::Style {
+                        color: Some(Color::from_rgb(0.9, 0.2, 0.2)),
+                    }
+                }))
+                .push(
+                    Button::new(Text::new("Dismiss").size(12))
+                        .on_press(Message::DismissStorageWarning)
+                        .padding(6)
+                )
+        });
+
         let theme_btn = Button::new(
             Text::new(match self.theme {
                 Theme::Light => "Dark Mode",
@@ -293,6 +1238,13 @@ impl VeriFileApp {
         .padding(10)
         .width(Length::Fill);
 
+        let page_btn = match self.page {
+            Page::Single => Button::new(Text::new("Batch Queue").size(14)).on_press(Message::ShowPage(Page::Queue)),
+            Page::Queue | Page::History => Button::new(Text::new("Single File").size(14)).on_press(Message::ShowPage(Page::Single)),
+        }
+        .padding(10)
+        .width(Length::Fill);
+
         let history_btn = Button::new(
             Text::new(if self.show_history { "Hide History" } else { "Show History" })
                 .size(14)
@@ -313,7 +1265,13 @@ impl VeriFileApp {
             .push(algo_label)
             .push(algo_picker)
             .push(algo_desc)
-            .push(Space::with_height(20))
+            .push_maybe(integrity_warning)
+            .push(extras_label)
+            .push(extras_scrollable)
+            .push(record_cancelled)
+            .push_maybe(storage_warning)
+            .push(Space::with_height(10))
+            .push(page_btn)
             .push(theme_btn)
             .push(history_btn);
 
@@ -327,11 +1285,7 @@ impl VeriFileApp {
 
             let mut history_list = Column::new().spacing(8);
             for (i, r) in self.past.iter().take(5).enumerate() {
-                let file_text_color = match r.status {
-                    VerificationStatus::Success => Color::from_rgb(0.2, 0.8, 0.2),
-                    VerificationStatus::Failed => Color::from_rgb(0.9, 0.2, 0.2),
-                    VerificationStatus::InProgress => Color::from_rgb(0.7, 0.7, 0.7),
-                };
+                let file_text_color = status_color(&r.status);
                 
                 let history_item = Column::new()
                     .spacing(4)
@@ -363,7 +1317,12 @@ impl VeriFileApp {
             let history_scrollable = Scrollable::new(history_list)
                 .height(Length::Fixed(200.0));
 
-            sidebar_content = sidebar_content.push(history_scrollable);
+            let browse_btn = Button::new(Text::new(format!("Browse All ({})", self.past.len())).size(12))
+                .on_press(Message::ShowPage(Page::History))
+                .padding(8)
+                .width(Length::Fill);
+
+            sidebar_content = sidebar_content.push(history_scrollable).push(browse_btn);
         }
 
         let sidebar_bg = self.sidebar_bg_color();
@@ -395,7 +1354,7 @@ impl VeriFileApp {
                 }
             });
 
-        let description = Text::new("Choose the file you want to verify")
+        let description = Text::new("Choose the file you want to verify, or drop it here together with its checksum file")
             .size(16)
             .style(move |_theme| {
                 iced::widget::text::Style {
@@ -455,6 +1414,16 @@ impl VeriFileApp {
                 )
         };
 
+        let pending_note = self.pending_checksum.as_ref().map(|(p, _)| {
+            Text::new(format!("Reference will be read from {} once a file is chosen", p.display()))
+                .size(14)
+                .style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(secondary_text),
+                    }
+                })
+        });
+
         let browse_btn = Button::new(
             Text::new("Browse Files")
                 .size(18)
@@ -463,6 +1432,63 @@ impl VeriFileApp {
         .padding(15)
         .width(Length::Fixed(200.0));
 
+        let manifest_btn = Button::new(
+            Text::new("Verify Checksum Manifest...")
+                .size(14)
+        )
+        .on_press(Message::ChooseManifest)
+        .padding(12);
+
+        let manifest_hint = Text::new("Check every file listed in a SHA256SUMS, *.md5 or similar file against its directory")
+            .size(12)
+            .style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(secondary_text),
+                }
+            });
+
+        let gen_label = Text::new(format!("Generate a {} manifest", self.generate_algorithm().name()))
+            .size(16)
+            .style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(text_color),
+                }
+            });
+        let gen_options = Row::new()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(
+                PickList::new(manifest::ManifestFormat::all(), Some(self.gen_format), Message::GenerateFormatSelected)
+                    .padding(8)
+                    .width(Length::Fixed(170.0))
+            )
+            .push(
+                TextInput::new("Include globs, e.g. *.iso", &self.gen_include)
+                    .on_input(Message::GenerateIncludeChanged)
+                    .padding(8)
+            )
+            .push(
+                TextInput::new("Exclude globs, e.g. *.tmp", &self.gen_exclude)
+                    .on_input(Message::GenerateExcludeChanged)
+                    .padding(8)
+            )
+            .push(
+                Checkbox::new("Absolute paths", self.gen_absolute)
+                    .on_toggle(Message::GenerateAbsoluteToggled)
+                    .text_size(14)
+            );
+        let gen_buttons = Row::new()
+            .spacing(10)
+            .push(Button::new(Text::new("From Files...").size(14)).on_press(Message::GenerateFromFiles).padding(12))
+            .push(Button::new(Text::new("From Folder...").size(14)).on_press(Message::GenerateFromFolder).padding(12));
+        let gen_note = self.generate_note.as_deref().map(|note| {
+            Text::new(note).size(13).style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(secondary_text),
+                }
+            })
+        });
+
         let next_btn = if self.chosen_file.is_some() {
             Button::new(
                 Text::new("Next: Upload Hash >")
@@ -489,11 +1515,21 @@ impl VeriFileApp {
             .push(description)
             .push(Space::with_height(20))
             .push(file_display)
+            .push_maybe(pending_note)
             .push(browse_btn)
             .push(Space::with_height(40))
-            .push(next_btn);
-
-        Container::new(content)
+            .push(next_btn)
+            .push(Space::with_height(20))
+            .push(rule::Rule::horizontal(1))
+            .push(manifest_btn)
+            .push(manifest_hint)
+            .push(rule::Rule::horizontal(1))
+            .push(gen_label)
+            .push(gen_options)
+            .push(gen_buttons)
+            .push_maybe(gen_note);
+
+        Container::new(Scrollable::new(content))
             .width(Length::Fill)
             .height(Length::Fill)
             .center_x(Length::Fill)
@@ -524,7 +1560,7 @@ impl VeriFileApp {
             });
 
         let hash_input = TextInput::new(
-            "Paste hash here (e.g., abc123def456...)",
+            "Paste hash here (hex, base64, SRI, sha256:..., Nix base32 or multihash)",
             &self.paste_hash,
         )
         .on_input(Message::PasteHashChanged)
@@ -532,6 +1568,26 @@ impl VeriFileApp {
         .size(16)
         .width(Length::Fill);
 
+        let detection_text = if self.paste_hash.trim().is_empty() {
+            String::new()
+        } else {
+            match self.detected.as_slice() {
+                [] => "Unrecognized hash format".to_string(),
+                [only] => format!("Detected algorithm: {}", only.name()),
+                many => format!(
+                    "Possible algorithms: {} (all will be checked)",
+                    many.iter().map(|a| a.name()).collect::<Vec<_>>().join(", ")
+                ),
+            }
+        };
+        let detection = Text::new(detection_text)
+            .size(13)
+            .style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(secondary_text),
+                }
+            });
+
         let load_file_btn = Button::new(
             Text::new("Load Hash from File")
                 .size(14)
@@ -582,7 +1638,15 @@ impl VeriFileApp {
             .push(description)
             .push(Space::with_height(20))
             .push(hash_input)
+            .push(detection)
             .push(load_file_btn)
+            .push_maybe(self.hash_file_note.as_deref().map(|note| {
+                Text::new(note).size(13).style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(secondary_text),
+                    }
+                })
+            }))
             .push(note)
             .push(Space::with_height(40))
             .push(button_row);
@@ -625,6 +1689,21 @@ impl VeriFileApp {
                 }
             });
 
+        // Files of known size get a bar; streams of unknown length keep the spinner.
+        let indicator: Element<'_, Message> = match self.progress.as_ref().and_then(Progress::fraction) {
+            Some(f) => ProgressBar::new(0.0..=1.0, f).height(12).width(Length::Fixed(480.0)).into(),
+            None => spinner.into(),
+        };
+        let detail = self.progress.as_ref().map(|p| {
+            Text::new(p.summary())
+                .size(14)
+                .style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(secondary_text),
+                    }
+                })
+        });
+
         let content = Column::new()
             .padding(40)
             .spacing(30)
@@ -632,8 +1711,14 @@ impl VeriFileApp {
             .align_x(Alignment::Center)
             .push(step_indicator)
             .push(title)
-            .push(spinner)
-            .push(description);
+            .push(indicator)
+            .push_maybe(detail)
+            .push(description)
+            .push_maybe(self.cancel.as_ref().map(|_| {
+                Button::new(Text::new("Cancel").size(16))
+                    .on_press(Message::CancelVerify)
+                    .padding(12)
+            }));
 
         Container::new(content)
             .width(Length::Fill)
@@ -647,19 +1732,41 @@ impl VeriFileApp {
         let step_indicator = self.step_indicator(3);
 
         let (title, title_color) = if let Some(rec) = &self.last_result {
-            match rec.status {
-                VerificationStatus::Success => (
-                    "Verification Successful!",
-                    Color::from_rgb(0.2, 0.7, 0.2),
-                ),
-                VerificationStatus::Failed => (
-                    "Verification Failed",
-                    Color::from_rgb(0.9, 0.2, 0.2),
-                ),
-                VerificationStatus::InProgress => (
-                    "In Progress",
-                    Color::from_rgb(0.5, 0.5, 0.5),
-                ),
+            match (rec.reverify, &rec.status) {
+                (Some(ReverifyOutcome::Unchanged), _) => ("File Unchanged", Color::from_rgb(0.2, 0.7, 0.2)),
+                (Some(ReverifyOutcome::Changed), _) => ("File Changed!", Color::from_rgb(0.9, 0.2, 0.2)),
+                (Some(ReverifyOutcome::Corrupted), _) => ("File Corrupted!", Color::from_rgb(0.9, 0.2, 0.2)),
+                (Some(ReverifyOutcome::Missing), _) => ("File Missing", WARNING_COLOR),
+                (None, status) => match status {
+                    VerificationStatus::Success => (
+                        "Verification Successful!",
+                        Color::from_rgb(0.2, 0.7, 0.2),
+                    ),
+                    VerificationStatus::Failed => (
+                        "Verification Failed",
+                        Color::from_rgb(0.9, 0.2, 0.2),
+                    ),
+                    VerificationStatus::Unverified => (
+                        "Hash Computed",
+                        Color::from_rgb(0.5, 0.5, 0.5),
+                    ),
+                    VerificationStatus::Missing => (
+                        "File Not Found",
+                        WARNING_COLOR,
+                    ),
+                    VerificationStatus::Error { .. } => (
+                        "Could Not Read File",
+                        Color::from_rgb(0.9, 0.2, 0.2),
+                    ),
+                    VerificationStatus::InProgress => (
+                        "In Progress",
+                        Color::from_rgb(0.5, 0.5, 0.5),
+                    ),
+                    VerificationStatus::Cancelled => (
+                        "Verification Cancelled",
+                        Color::from_rgb(0.5, 0.5, 0.5),
+                    ),
+                },
             }
         } else {
             ("Error", Color::from_rgb(0.9, 0.2, 0.2))
@@ -715,7 +1822,7 @@ impl VeriFileApp {
                 .push(
                     Column::new()
                         .spacing(5)
-                        .push(Text::new("Computed Hash:").size(14).style(move |_theme| {
+                        .push(Text::new(if rec.manifest.is_some() { "Manifest Digest:" } else { "Computed Hash:" }).size(14).style(move |_theme| {
                             iced::widget::text::Style {
                                 color: Some(secondary_text),
                             }
@@ -742,13 +1849,42 @@ impl VeriFileApp {
                         )
                 );
 
+            let extra_digests: Vec<_> = rec.digests.iter().filter(|(a, _)| *a != &rec.algorithm).collect();
+            if !extra_digests.is_empty() {
+                let mut digest_list = Column::new().spacing(8);
+                for (a, hex) in extra_digests {
+                    digest_list = digest_list.push(
+                        Text::new(format!("{}: {}", a.name(), hex)).size(13).style(move |_theme| {
+                            iced::widget::text::Style {
+                                color: Some(text_color),
+                            }
+                        })
+                    );
+                }
+                details = details
+                    .push(Space::with_height(5))
+                    .push(
+                        Column::new()
+                            .spacing(5)
+                            .push(Text::new("Additional Digests:").size(14).style(move |_theme| {
+                                iced::widget::text::Style {
+                                    color: Some(secondary_text),
+                                }
+                            }))
+                            .push(digest_list)
+                    );
+            }
+
             if let Some(ref_hash) = &rec.reference_hash {
                 details = details
                     .push(Space::with_height(5))
                     .push(
                         Column::new()
                             .spacing(5)
-                            .push(Text::new("Reference Hash:").size(14).style(move |_theme| {
+                            .push(Text::new(match rec.reference_encoding {
+                                Some(enc) => format!("Reference Hash ({}):", enc.name()),
+                                None => "Reference Hash:".to_string(),
+                            }).size(14).style(move |_theme| {
                                 iced::widget::text::Style {
                                     color: Some(secondary_text),
                                 }
@@ -775,8 +1911,142 @@ impl VeriFileApp {
                             )
                     );
             }
+
+            if let Some(meta) = &rec.metadata {
+                let mut lines = vec![format!("Size: {} ({} bytes)", progress::format_bytes(meta.size), meta.size)];
+                if let Some(modified) = meta.modified {
+                    lines.push(format!("Modified: {}", modified.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")));
+                }
+                if let (Some(inode), Some(device)) = (meta.inode, meta.device) {
+                    lines.push(format!("Inode: {} on device {}", inode, device));
+                }
+                lines.push(match meta.mode {
+                    Some(mode) => format!("Permissions: {} ({:o})", meta.permissions(), mode),
+                    None => format!("Permissions: {}", meta.permissions()),
+                });
+                if let (Some(uid), Some(gid)) = (meta.uid, meta.gid) {
+                    lines.push(format!("Owner: uid {}, gid {}", uid, gid));
+                }
+                let earlier = rec
+                    .parent_id
+                    .as_ref()
+                    .and_then(|parent| self.past.iter().find(|p| &p.id == parent))
+                    .and_then(|p| p.metadata.as_ref());
+                if let Some(earlier) = earlier {
+                    let changes = meta.changes_since(earlier);
+                    lines.push(if changes.is_empty() {
+                        "Metadata unchanged since the last verification".to_string()
+                    } else {
+                        format!("Changed since the last verification: {}", changes.join(", "))
+                    });
+                }
+
+                let mut meta_list = Column::new().spacing(4);
+                for line in lines {
+                    meta_list = meta_list.push(Text::new(line).size(13).style(move |_theme| {
+                        iced::widget::text::Style {
+                            color: Some(text_color),
+                        }
+                    }));
+                }
+                details = details
+                    .push(Space::with_height(5))
+                    .push(
+                        Column::new()
+                            .spacing(5)
+                            .push(Text::new("File Metadata:").size(14).style(move |_theme| {
+                                iced::widget::text::Style {
+                                    color: Some(secondary_text),
+                                }
+                            }))
+                            .push(meta_list)
+                    );
+            }
         }
 
+        if let Some(report) = &self.manifest_report {
+            let mut entry_list = Column::new().spacing(6);
+            for r in &report.results {
+                let color = match r.status {
+                    manifest::EntryStatus::Ok => Color::from_rgb(0.2, 0.7, 0.2),
+                    manifest::EntryStatus::Failed => Color::from_rgb(0.9, 0.2, 0.2),
+                    manifest::EntryStatus::Missing => WARNING_COLOR,
+                };
+                let mut line = format!("{}: {}", r.entry.file_name.as_deref().unwrap_or_default(), r.status.label());
+                if let Some(err) = &r.error {
+                    line.push_str(&format!(" ({})", err));
+                }
+                entry_list = entry_list.push(
+                    Text::new(line).size(13).style(move |_theme| {
+                        iced::widget::text::Style {
+                            color: Some(color),
+                        }
+                    })
+                );
+            }
+            for d in &report.diagnostics {
+                entry_list = entry_list.push(
+                    Text::new(format!("line {}: {}", d.line, d.message)).size(12).style(move |_theme| {
+                        iced::widget::text::Style {
+                            color: Some(secondary_text),
+                        }
+                    })
+                );
+            }
+            details = details
+                .push(Space::with_height(5))
+                .push(Text::new(&self.status_message).size(16).style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(text_color),
+                    }
+                }))
+                .push(
+                    Container::new(Scrollable::new(entry_list).height(Length::Fixed(220.0)))
+                        .padding(10)
+                        .width(Length::Fill)
+                        .style(move |_theme| {
+                            iced::widget::container::Style {
+                                background: Some(iced::Background::Color(container_bg)),
+                                border: Border {
+                                    color: border_color,
+                                    width: 1.0,
+                                    radius: 4.0.into(),
+                                },
+                                ..Default::default()
+                            }
+                        })
+                );
+        }
+
+        let parent_note = self.last_result.as_ref().and_then(|r| r.parent_id.as_ref()).map(|parent| {
+            let when = self
+                .past
+                .iter()
+                .find(|p| &p.id == parent)
+                .map(|p| p.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "an earlier run".to_string());
+            Text::new(format!("Re-verification against the digest recorded {}", when))
+                .size(14)
+                .style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(secondary_text),
+                    }
+                })
+        });
+
+        let integrity_note = self.last_result.as_ref().filter(|r| r.integrity_only).map(|r| {
+            Text::new(format!(
+                "{} is an integrity-only checksum: a match rules out accidental corruption, not tampering.",
+                r.algorithm.name()
+            ))
+            .size(14)
+            .style(|_theme| {
+                iced::widget::text::Style {
+                    color: Some(WARNING_COLOR),
+                }
+            })
+        });
+
         let new_verification_btn = Button::new(
             Text::new("New Verification")
                 .size(16)
@@ -792,6 +2062,15 @@ impl VeriFileApp {
             .align_x(Alignment::Center)
             .push(step_indicator)
             .push(title_text)
+            .push_maybe(self.detection_note.as_deref().map(|note| {
+                Text::new(note).size(14).style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(secondary_text),
+                    }
+                })
+            }))
+            .push_maybe(parent_note)
+            .push_maybe(integrity_note)
             .push(Space::with_height(20))
             .push(details)
             .push(Space::with_height(30))
@@ -804,6 +2083,400 @@ impl VeriFileApp {
             .into()
     }
 
+    fn view_queue(&self) -> Element<'_, Message> {
+        let text_color = self.text_color();
+        let secondary_text = self.secondary_text_color();
+        let container_bg = self.container_bg_color();
+        let border_color = self.border_color();
+
+        let title = Text::new("Verification Queue")
+            .size(32)
+            .style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(text_color),
+                }
+            });
+
+        let running = self.queue.iter().filter(|i| matches!(i.state, QueueState::Running(..))).count();
+        let count = |status: VerificationStatus| {
+            self.queue
+                .iter()
+                .filter(|i| matches!(&i.state, QueueState::Done(r) if r.status == status))
+                .count()
+        };
+        let errors = self
+            .queue
+            .iter()
+            .filter(|i| match &i.state {
+                QueueState::Error(_) => true,
+                QueueState::Done(r) => matches!(r.status, VerificationStatus::Missing | VerificationStatus::Error { .. }),
+                _ => false,
+            })
+            .count();
+        let summary = Text::new(format!(
+            "{} files: {} running, {} OK, {} unverified, {} failed, {} errors",
+            self.queue.len(),
+            running,
+            count(VerificationStatus::Success),
+            count(VerificationStatus::Unverified),
+            count(VerificationStatus::Failed),
+            errors
+        ))
+        .size(14)
+        .style(move |_theme| {
+            iced::widget::text::Style {
+                color: Some(secondary_text),
+            }
+        });
+
+        let has_pending = self.queue.iter().any(|i| matches!(i.state, QueueState::Pending));
+        let idle = running == 0;
+        let controls = Row::new()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(Button::new(Text::new("Add Files...").size(14)).on_press(Message::QueueAddFiles).padding(12))
+            .push(
+                Button::new(Text::new("Start").size(14))
+                    .on_press_maybe((has_pending && !self.queue_running).then_some(Message::QueueStart))
+                    .padding(12),
+            )
+            .push(
+                Button::new(Text::new("Cancel").size(14))
+                    .on_press_maybe((!idle).then_some(Message::QueueCancel))
+                    .padding(12),
+            )
+            .push(
+                Button::new(Text::new("Retry All").size(14))
+                    .on_press_maybe((idle && !self.queue.is_empty()).then_some(Message::QueueRetry))
+                    .padding(12),
+            )
+            .push(
+                Button::new(Text::new("Clear").size(14))
+                    .on_press_maybe((idle && !self.queue.is_empty()).then_some(Message::QueueClear))
+                    .padding(12),
+            )
+            .push(Space::with_width(Length::Fill))
+            .push(Text::new("Workers").size(14).style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(secondary_text),
+                }
+            }))
+            .push(PickList::new((1..=8).collect::<Vec<usize>>(), Some(self.queue_workers), Message::QueueWorkersSelected).padding(8));
+
+        let mut rows = Column::new().spacing(10);
+        for item in &self.queue {
+            let id = item.id;
+            let is_running = matches!(item.state, QueueState::Running(..));
+            let (status, status_color) = match &item.state {
+                QueueState::Pending => ("Queued".to_string(), secondary_text),
+                QueueState::Running(p, _) => (
+                    match p.fraction() {
+                        Some(f) => format!("{:.0}% · {}", f * 100.0, p.summary()),
+                        None => p.summary(),
+                    },
+                    self.accent_color(),
+                ),
+                QueueState::Done(rec) => match &rec.status {
+                    VerificationStatus::Success => ("OK".to_string(), Color::from_rgb(0.2, 0.7, 0.2)),
+                    VerificationStatus::Unverified => ("Hashed (no reference)".to_string(), text_color),
+                    VerificationStatus::Failed => ("FAILED - hash mismatch".to_string(), Color::from_rgb(0.9, 0.2, 0.2)),
+                    VerificationStatus::Missing => ("File not found".to_string(), WARNING_COLOR),
+                    VerificationStatus::Error { message, .. } => (message.clone(), Color::from_rgb(0.9, 0.2, 0.2)),
+                    VerificationStatus::Cancelled => ("Cancelled".to_string(), secondary_text),
+                    VerificationStatus::InProgress => ("In progress".to_string(), secondary_text),
+                },
+                QueueState::Error(e) => (e.clone(), Color::from_rgb(0.9, 0.2, 0.2)),
+            };
+            let name = item.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+
+            let header = Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(Text::new(name).size(16).width(Length::Fill).style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(text_color),
+                    }
+                }))
+                .push(PickList::new(Algorithm::all(), Some(item.algorithm.clone()), move |a| Message::QueueAlgorithmSelected(id, a)).padding(6))
+                .push(
+                    Button::new(Text::new("Remove").size(12))
+                        .on_press_maybe((!is_running).then_some(Message::QueueRemove(id)))
+                        .padding(6),
+                );
+            let reference = TextInput::new("Reference hash (optional)", &item.reference)
+                .on_input_maybe((!is_running).then_some(move |s| Message::QueueReferenceChanged(id, s)))
+                .padding(8)
+                .size(13);
+            let bar = match &item.state {
+                QueueState::Running(p, _) => p.fraction().map(|f| ProgressBar::new(0.0..=1.0, f).height(6)),
+                _ => None,
+            };
+            let status = Text::new(status).size(13).style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(status_color),
+                }
+            });
+
+            rows = rows.push(
+                Container::new(
+                    Column::new()
+                        .spacing(8)
+                        .push(header)
+                        .push(reference)
+                        .push_maybe(bar)
+                        .push(status),
+                )
+                .padding(12)
+                .width(Length::Fill)
+                .style(move |_theme| {
+                    iced::widget::container::Style {
+                        background: Some(iced::Background::Color(container_bg)),
+                        border: Border {
+                            color: border_color,
+                            width: 1.0,
+                            radius: 4.0.into(),
+                        },
+                        ..Default::default()
+                    }
+                }),
+            );
+        }
+        if self.queue.is_empty() {
+            let tertiary_text = self.tertiary_text_color();
+            rows = rows.push(Text::new("No files queued").size(16).style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(tertiary_text),
+                }
+            }));
+        }
+
+        let content = Column::new()
+            .padding(40)
+            .spacing(20)
+            .width(Length::Fill)
+            .push(title)
+            .push(summary)
+            .push(controls)
+            .push(Scrollable::new(rows).height(Length::Fill));
+
+        Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn view_history(&self) -> Element<'_, Message> {
+        let text_color = self.text_color();
+        let secondary_text = self.secondary_text_color();
+        let tertiary_text = self.tertiary_text_color();
+        let border_color = self.border_color();
+        let q = &self.history_query;
+
+        let title = Text::new("Verification History")
+            .size(32)
+            .style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(text_color),
+                }
+            });
+
+        let chain_status = self.history_chain.as_ref().map(|result| {
+            let (text, color) = match result {
+                Ok(report) if report.is_intact() => (
+                    format!(
+                        "History chain intact: {} records{}",
+                        report.records,
+                        if report.signed { ", head signature valid" } else { "" }
+                    ),
+                    Color::from_rgb(0.2, 0.7, 0.3),
+                ),
+                Ok(report) => (
+                    report.issues.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("\n"),
+                    Color::from_rgb(0.9, 0.2, 0.2),
+                ),
+                Err(e) => (format!("Could not check history: {}", e), Color::from_rgb(0.9, 0.2, 0.2)),
+            };
+            Text::new(text).size(13).style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(color),
+                }
+            })
+        });
+        let title = Row::new()
+            .spacing(20)
+            .align_y(Alignment::Center)
+            .push(title)
+            .push(Space::with_width(Length::Fill))
+            .push(PickList::new(ExportFormat::all(), Some(self.export_format), Message::ExportFormatSelected).text_size(13).padding(8))
+            .push(Button::new(Text::new("Export...").size(13)).on_press(Message::ExportHistory).padding(8))
+            .push(Button::new(Text::new("Import Logs...").size(13)).on_press(Message::ImportLogs).padding(8))
+            .push(Button::new(Text::new("Verify Chain").size(13)).on_press(Message::VerifyHistoryChain).padding(8));
+        let history_note = self.history_note.as_deref().map(|note| {
+            Text::new(note).size(13).style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(secondary_text),
+                }
+            })
+        });
+
+        let search = TextInput::new("Search file name, path or hash", &q.text)
+            .on_input(Message::HistorySearchChanged)
+            .padding(10)
+            .width(Length::Fill);
+
+        let mut statuses = vec![Choice::All];
+        statuses.extend(VerificationStatus::all().into_iter().map(Choice::Only));
+        let status_choice = q.status.clone().map_or(Choice::All, Choice::Only);
+        let mut algorithms = vec![Choice::All];
+        algorithms.extend(Algorithm::all().into_iter().map(Choice::Only));
+        let algo_choice = q.algorithm.clone().map_or(Choice::All, Choice::Only);
+
+        let date_input = |placeholder, value: &String, valid: bool, on_input: fn(String) -> Message| {
+            TextInput::new(placeholder, value)
+                .on_input(on_input)
+                .padding(10)
+                .width(Length::Fixed(130.0))
+                .style(move |theme, status| {
+                    let mut style = iced::widget::text_input::default(theme, status);
+                    if !valid {
+                        style.border.color = WARNING_COLOR;
+                    }
+                    style
+                })
+        };
+        let from_valid = self.history_from.trim().is_empty() || q.from.is_some();
+        let to_valid = self.history_to.trim().is_empty() || q.to.is_some();
+
+        let filters = Row::new()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(search)
+            .push(PickList::new(statuses, Some(status_choice), Message::HistoryStatusSelected).padding(10))
+            .push(PickList::new(algorithms, Some(algo_choice), Message::HistoryAlgorithmSelected).padding(10))
+            .push(date_input("From YYYY-MM-DD", &self.history_from, from_valid, Message::HistoryFromChanged))
+            .push(date_input("To YYYY-MM-DD", &self.history_to, to_valid, Message::HistoryToChanged));
+
+        // Column widths shared by the header and the rows.
+        let widths = [Length::Fixed(150.0), Length::Fill, Length::Fixed(110.0), Length::Fixed(100.0), Length::Fixed(170.0), Length::Fixed(90.0)];
+        let cell_width = move |i: usize| widths[i];
+        let header_cell = |i: usize, label: &str, key: Option<SortKey>| -> Element<'_, Message> {
+            let arrow = match key {
+                Some(k) if k == q.sort => if q.descending { " ▼" } else { " ▲" },
+                _ => "",
+            };
+            let label = Text::new(format!("{}{}", label, arrow)).size(13);
+            match key {
+                Some(k) => Button::new(label)
+                    .on_press(Message::HistorySortBy(k))
+                    .style(iced::widget::button::text)
+                    .padding(4)
+                    .width(cell_width(i))
+                    .into(),
+                None => Container::new(label).padding(4).width(cell_width(i)).into(),
+            }
+        };
+        let header = Row::new()
+            .spacing(10)
+            .push(header_cell(0, "Date", Some(SortKey::Date)))
+            .push(header_cell(1, "File", Some(SortKey::FileName)))
+            .push(header_cell(2, "Algorithm", Some(SortKey::Algorithm)))
+            .push(header_cell(3, "Status", Some(SortKey::Status)))
+            .push(header_cell(4, "Hash", None))
+            .push(header_cell(5, "", None));
+
+        let small = move |s: String, color: Color, i: usize| {
+            Text::new(s).size(13).width(cell_width(i)).style(move |_theme| {
+                iced::widget::text::Style {
+                    color: Some(color),
+                }
+            })
+        };
+        let mut rows = Column::new().spacing(6);
+        for &i in &self.history_page.indices {
+            let r = &self.past[i];
+            let hash = if r.computed_hash.len() > 16 {
+                format!("{}…", &r.computed_hash[..16])
+            } else {
+                r.computed_hash.clone()
+            };
+            let file = Column::new()
+                .width(cell_width(1))
+                .push(Text::new(&r.file_name).size(13).style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(text_color),
+                    }
+                }))
+                .push(Text::new(r.file_path.display().to_string()).size(11).style(move |_theme| {
+                    iced::widget::text::Style {
+                        color: Some(tertiary_text),
+                    }
+                }));
+            rows = rows
+                .push(
+                    Row::new()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(small(r.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(), secondary_text, 0))
+                        .push(file)
+                        .push(small(r.algorithm.name().to_string(), text_color, 2))
+                        .push(small(r.reverify.map_or(r.status.label(), |o| o.label()).to_string(), status_color(&r.status), 3))
+                        .push(small(hash, secondary_text, 4))
+                        .push(
+                            Button::new(Text::new("Re-verify").size(12))
+                                .on_press_maybe((!r.computed_hash.is_empty() && !self.is_verifying).then(|| Message::Reverify(r.id.clone())))
+                                .padding(6)
+                                .width(cell_width(5)),
+                        ),
+                )
+                .push(rule::Rule::horizontal(1).style(move |theme| {
+                    iced::widget::rule::Style {
+                        color: border_color,
+                        ..iced::widget::rule::default(theme)
+                    }
+                }));
+        }
+        if self.history_page.indices.is_empty() {
+            rows = rows.push(small("No matching verifications".to_string(), tertiary_text, 1));
+        }
+
+        let page = self.history_page.page;
+        let pager = Row::new()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(
+                Button::new(Text::new("< Prev").size(13))
+                    .on_press_maybe((page > 0).then(|| Message::HistoryPageSelected(page - 1)))
+                    .padding(8),
+            )
+            .push(small(
+                format!("Page {} of {} · {} matches", page + 1, self.history_page.page_count, self.history_page.total),
+                secondary_text,
+                1,
+            ))
+            .push(
+                Button::new(Text::new("Next >").size(13))
+                    .on_press_maybe((page + 1 < self.history_page.page_count).then(|| Message::HistoryPageSelected(page + 1)))
+                    .padding(8),
+            );
+
+        let content = Column::new()
+            .padding(40)
+            .spacing(20)
+            .width(Length::Fill)
+            .push(title)
+            .push_maybe(chain_status)
+            .push_maybe(history_note)
+            .push(filters)
+            .push(header)
+            .push(Scrollable::new(rows).height(Length::Fill))
+            .push(pager);
+
+        Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
     fn step_indicator(&self, current: u8) -> Element<'_, Message> {
         let step1_color = if current >= 1 { Color::from_rgb(0.2, 0.5, 0.8) } else { Color::from_rgb(0.7, 0.7, 0.7) };
         let step2_color = if current >= 2 { Color::from_rgb(0.2, 0.5, 0.8) } else { Color::from_rgb(0.7, 0.7, 0.7) };
@@ -859,16 +2532,6 @@ impl VeriFileApp {
             .into()
     }
 
-    fn get_algorithm_description(&self) -> &'static str {
-        match self.algorithm {
-            Algorithm::Blake3 => "Fast, secure cryptographic hash (Recommended)",
-            Algorithm::Sha256 => "Industry standard, widely used",
-            Algorithm::Sha512 => "Higher security, larger output",
-            Algorithm::Sha3_256 => "Latest SHA-3 standard",
-            Algorithm::Md5 => "Legacy, not recommended for security",
-        }
-    }
-
     // Theme color helpers
     fn bg_color(&self) -> Color {
         match self.theme {
@@ -923,3 +2586,64 @@ impl VeriFileApp {
         Color::from_rgb(0.2, 0.5, 0.8)
     }
 }
+
+/// Run `job` on a worker thread, streaming throttled `on_progress` messages
+/// and then one `done` message: `None` if the job was cancelled, otherwise
+/// the record or error.
+fn run_in_background(
+    job: impl FnOnce(&mut dyn FnMut(u64), &CancelToken) -> Result<VerificationRecord> + Send + 'static,
+    cancel: CancelToken,
+    on_progress: impl Fn(u64) -> Message + Send + 'static,
+    done: impl FnOnce(Option<Result<VerificationRecord, String>>) -> Message + Send + 'static,
+) -> Task<Message> {
+    let (tx, rx) = mpsc::unbounded();
+    std::thread::spawn(move || {
+        let mut last = Instant::now();
+        let result = job(&mut |bytes| {
+            if last.elapsed() >= progress::UPDATE_INTERVAL {
+                last = Instant::now();
+                let _ = tx.unbounded_send(on_progress(bytes));
+            }
+        }, &cancel);
+        let result = match result {
+            Err(e) if e.is::<Cancelled>() => None,
+            other => Some(other.map_err(|e| format!("Hash compute error: {:?}", e))),
+        };
+        let _ = tx.unbounded_send(done(result));
+    });
+    Task::stream(rx)
+}
+
+/// Hash `path` for the single-file and queue flows. A file that cannot be
+/// read still gives a record, `Missing` or `Error`, so it reaches history.
+fn verify_or_record(
+    path: &std::path::Path,
+    algo: &Algorithm,
+    extras: &[Algorithm],
+    reference: Option<&str>,
+    progress: &mut dyn FnMut(u64),
+    cancel: &CancelToken,
+) -> Result<VerificationRecord> {
+    match file_ops::verify_file_with_progress(path, algo, extras, reference, progress, Some(cancel)) {
+        Err(e) if !e.is::<Cancelled>() => Ok(file_ops::error_record(path, algo, reference, &e)),
+        other => other,
+    }
+}
+
+fn status_color(status: &VerificationStatus) -> Color {
+    match status {
+        VerificationStatus::Success => Color::from_rgb(0.2, 0.8, 0.2),
+        VerificationStatus::Failed | VerificationStatus::Error { .. } => Color::from_rgb(0.9, 0.2, 0.2),
+        VerificationStatus::Missing => WARNING_COLOR,
+        VerificationStatus::Unverified | VerificationStatus::InProgress | VerificationStatus::Cancelled => {
+            Color::from_rgb(0.7, 0.7, 0.7)
+        }
+    }
+}
+
+fn single_file_done(result: Option<Result<VerificationRecord, String>>) -> Message {
+    match result {
+        Some(result) => Message::VerifyComplete(result),
+        None => Message::VerifyCancelled,
+    }
+}
@@ -1,62 +1,303 @@
 use crate::models::Algorithm;
 use anyhow::Result;
+use std::collections::BTreeMap;
 use std::io::Read;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 
-pub fn compute_hash_for_reader<R: Read>(mut reader: R, algorithm: &Algorithm) -> Result<String> {
-    match algorithm {
-        Algorithm::Blake3 => {
-            let mut hasher = blake3::Hasher::new();
-            let mut buf = [0u8; 64 * 1024];
-            loop {
-                let n = reader.read(&mut buf)?;
-                if n == 0 { break; }
-                hasher.update(&buf[..n]);
-            }
-            Ok(hasher.finalize().to_hex().to_string())
-        }
-        Algorithm::Sha256 => {
-            use sha2::{Digest, Sha256};
-            let mut hasher = Sha256::new();
-            let mut buf = [0u8; 64 * 1024];
-            loop {
-                let n = reader.read(&mut buf)?;
-                if n == 0 { break; }
-                hasher.update(&buf[..n]);
-            }
-            Ok(hex::encode(hasher.finalize()))
-        }
-        Algorithm::Sha512 => {
-            use sha2::{Digest, Sha512};
-            let mut hasher = Sha512::new();
-            let mut buf = [0u8; 64 * 1024];
-            loop {
-                let n = reader.read(&mut buf)?;
-                if n == 0 { break; }
-                hasher.update(&buf[..n]);
-            }
-            Ok(hex::encode(hasher.finalize()))
+const READ_BUF_SIZE: usize = 64 * 1024;
+
+/// A streaming digest implementation.
+///
+/// Each supported algorithm provides one implementation of this trait and one
+/// entry in `REGISTRY`; everything else (picker, parser, storage) resolves
+/// through `Algorithm` and picks it up from there.
+pub trait DigestEngine: Send {
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self: Box<Self>) -> Vec<u8>;
+    /// Digest length in bytes.
+    fn output_len(&self) -> usize;
+    /// Canonical display name, e.g. "SHA-256".
+    fn name(&self) -> &'static str;
+    /// Alternative spellings accepted when parsing, matched case-insensitively.
+    fn aliases(&self) -> &'static [&'static str] { &[] }
+    /// One-line description shown under the algorithm picker.
+    fn description(&self) -> &'static str { "" }
+    /// False for plain checksums that an attacker can trivially forge.
+    fn is_cryptographic(&self) -> bool { true }
+}
+
+/// Wraps a RustCrypto `Digest` type as a `DigestEngine`.
+macro_rules! digest_engine {
+    ($engine:ident, $inner:ty, $name:expr, [$($alias:expr),*], $desc:expr) => {
+        struct $engine($inner);
+
+        impl DigestEngine for $engine {
+            fn update(&mut self, data: &[u8]) { sha2::Digest::update(&mut self.0, data); }
+            fn finalize(self: Box<Self>) -> Vec<u8> { sha2::Digest::finalize(self.0).to_vec() }
+            fn output_len(&self) -> usize { <$inner as sha2::Digest>::output_size() }
+            fn name(&self) -> &'static str { $name }
+            fn aliases(&self) -> &'static [&'static str] { &[$($alias),*] }
+            fn description(&self) -> &'static str { $desc }
         }
-        Algorithm::Sha3_256 => {
-            use sha3::{Digest, Sha3_256};
-            let mut hasher = Sha3_256::new();
-            let mut buf = [0u8; 64 * 1024];
-            loop {
-                let n = reader.read(&mut buf)?;
-                if n == 0 { break; }
-                hasher.update(&buf[..n]);
-            }
-            Ok(hex::encode(hasher.finalize()))
+    };
+}
+
+struct Blake3Engine(blake3::Hasher);
+
+impl DigestEngine for Blake3Engine {
+    fn update(&mut self, data: &[u8]) { self.0.update(data); }
+    fn finalize(self: Box<Self>) -> Vec<u8> { self.0.finalize().as_bytes().to_vec() }
+    fn output_len(&self) -> usize { blake3::OUT_LEN }
+    fn name(&self) -> &'static str { "BLAKE3" }
+    fn aliases(&self) -> &'static [&'static str] { &["blake3", "b3"] }
+    fn description(&self) -> &'static str { "Fast, secure cryptographic hash (Recommended)" }
+}
+
+digest_engine!(Sha1Engine, sha1::Sha1, "SHA-1", ["sha1"],
+    "Legacy, broken for collisions; still published by many mirrors");
+digest_engine!(Sha224Engine, sha2::Sha224, "SHA-224", ["sha224", "sha2-224"],
+    "Truncated SHA-256 variant");
+digest_engine!(Sha256Engine, sha2::Sha256, "SHA-256", ["sha256", "sha2-256"],
+    "Industry standard, widely used");
+digest_engine!(Sha384Engine, sha2::Sha384, "SHA-384", ["sha384", "sha2-384"],
+    "Truncated SHA-512 variant");
+digest_engine!(Sha512Engine, sha2::Sha512, "SHA-512", ["sha512", "sha2-512"],
+    "Higher security, larger output");
+digest_engine!(Sha512_256Engine, sha2::Sha512_256, "SHA-512/256",
+    ["sha512/256", "sha512-256", "sha512_256", "sha512t256", "sha2-512/256"],
+    "SHA-512 core with a 256-bit output, fast on 64-bit CPUs");
+digest_engine!(Sha3_224Engine, sha3::Sha3_224, "SHA3-224", ["sha3_224"],
+    "SHA-3 standard, 224-bit output");
+digest_engine!(Sha3_256Engine, sha3::Sha3_256, "SHA3-256", ["sha3_256", "sha3"],
+    "Latest SHA-3 standard");
+digest_engine!(Sha3_384Engine, sha3::Sha3_384, "SHA3-384", ["sha3_384"],
+    "SHA-3 standard, 384-bit output");
+digest_engine!(Sha3_512Engine, sha3::Sha3_512, "SHA3-512", ["sha3_512"],
+    "SHA-3 standard, 512-bit output");
+digest_engine!(Blake2b512Engine, blake2::Blake2b512, "BLAKE2b-512", ["blake2b", "blake2b512", "b2"],
+    "Fast and secure, used by b2sum and Argon2");
+digest_engine!(Blake2s256Engine, blake2::Blake2s256, "BLAKE2s-256", ["blake2s", "blake2s256"],
+    "BLAKE2 tuned for 32-bit and small devices");
+digest_engine!(Keccak256Engine, sha3::Keccak256, "Keccak-256", ["keccak256", "keccak"],
+    "Original Keccak padding, as used by Ethereum");
+
+struct Md5Engine(md5::Context);
+
+impl DigestEngine for Md5Engine {
+    fn update(&mut self, data: &[u8]) { self.0.consume(data); }
+    fn finalize(self: Box<Self>) -> Vec<u8> { self.0.finalize().0.to_vec() }
+    fn output_len(&self) -> usize { 16 }
+    fn name(&self) -> &'static str { "MD5" }
+    fn aliases(&self) -> &'static [&'static str] { &["md5"] }
+    fn description(&self) -> &'static str { "Legacy, not recommended for security" }
+}
+
+type EngineFactory = fn() -> Box<dyn DigestEngine>;
+
+struct Crc32Engine(crc32fast::Hasher);
+
+impl DigestEngine for Crc32Engine {
+    fn update(&mut self, data: &[u8]) { self.0.update(data); }
+    fn finalize(self: Box<Self>) -> Vec<u8> { self.0.finalize().to_be_bytes().to_vec() }
+    fn output_len(&self) -> usize { 4 }
+    fn name(&self) -> &'static str { "CRC32" }
+    fn aliases(&self) -> &'static [&'static str] { &["crc32", "crc-32", "sfv"] }
+    fn description(&self) -> &'static str { "ZIP/SFV checksum, integrity only" }
+    fn is_cryptographic(&self) -> bool { false }
+}
+
+struct Crc32cEngine(u32);
+
+impl DigestEngine for Crc32cEngine {
+    fn update(&mut self, data: &[u8]) { self.0 = crc32c::crc32c_append(self.0, data); }
+    fn finalize(self: Box<Self>) -> Vec<u8> { self.0.to_be_bytes().to_vec() }
+    fn output_len(&self) -> usize { 4 }
+    fn name(&self) -> &'static str { "CRC32C" }
+    fn aliases(&self) -> &'static [&'static str] { &["crc32c", "crc-32c", "castagnoli"] }
+    fn description(&self) -> &'static str { "Castagnoli CRC used by ceph and iSCSI, integrity only" }
+    fn is_cryptographic(&self) -> bool { false }
+}
+
+struct Adler32Engine(adler2::Adler32);
+
+impl DigestEngine for Adler32Engine {
+    fn update(&mut self, data: &[u8]) { self.0.write_slice(data); }
+    fn finalize(self: Box<Self>) -> Vec<u8> { self.0.checksum().to_be_bytes().to_vec() }
+    fn output_len(&self) -> usize { 4 }
+    fn name(&self) -> &'static str { "Adler-32" }
+    fn aliases(&self) -> &'static [&'static str] { &["adler32", "adler"] }
+    fn description(&self) -> &'static str { "zlib/rsync rolling checksum, integrity only" }
+    fn is_cryptographic(&self) -> bool { false }
+}
+
+struct Xxh3_64Engine(Box<xxhash_rust::xxh3::Xxh3>);
+
+impl DigestEngine for Xxh3_64Engine {
+    fn update(&mut self, data: &[u8]) { self.0.update(data); }
+    fn finalize(self: Box<Self>) -> Vec<u8> { self.0.digest().to_be_bytes().to_vec() }
+    fn output_len(&self) -> usize { 8 }
+    fn name(&self) -> &'static str { "XXH3-64" }
+    fn aliases(&self) -> &'static [&'static str] { &["xxh3", "xxh3_64", "xxhash3"] }
+    fn description(&self) -> &'static str { "Very fast non-cryptographic hash, integrity only" }
+    fn is_cryptographic(&self) -> bool { false }
+}
+
+struct Xxh3_128Engine(Box<xxhash_rust::xxh3::Xxh3>);
+
+impl DigestEngine for Xxh3_128Engine {
+    fn update(&mut self, data: &[u8]) { self.0.update(data); }
+    fn finalize(self: Box<Self>) -> Vec<u8> { self.0.digest128().to_be_bytes().to_vec() }
+    fn output_len(&self) -> usize { 16 }
+    fn name(&self) -> &'static str { "XXH128" }
+    fn aliases(&self) -> &'static [&'static str] { &["xxh128", "xxh3_128", "xxh3-128"] }
+    fn description(&self) -> &'static str { "128-bit xxHash, integrity only" }
+    fn is_cryptographic(&self) -> bool { false }
+}
+
+/// Registry entry tying an `Algorithm` to the engine that implements it.
+pub struct AlgorithmInfo {
+    pub algorithm: Algorithm,
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    pub output_len: usize,
+    pub integrity_only: bool,
+    create: EngineFactory,
+}
+
+impl AlgorithmInfo {
+    pub fn engine(&self) -> Box<dyn DigestEngine> {
+        (self.create)()
+    }
+
+    /// True if `s` is the canonical name or one of the aliases.
+    pub fn matches_name(&self, s: &str) -> bool {
+        self.name.eq_ignore_ascii_case(s) || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(s))
+    }
+}
+
+/// Order here is the order shown in the algorithm picker.
+const REGISTRY: &[(Algorithm, EngineFactory)] = &[
+    (Algorithm::Blake3, || Box::new(Blake3Engine(blake3::Hasher::new()))),
+    (Algorithm::Sha256, || Box::new(Sha256Engine(Default::default()))),
+    (Algorithm::Sha512, || Box::new(Sha512Engine(Default::default()))),
+    (Algorithm::Sha3_256, || Box::new(Sha3_256Engine(Default::default()))),
+    (Algorithm::Sha224, || Box::new(Sha224Engine(Default::default()))),
+    (Algorithm::Sha384, || Box::new(Sha384Engine(Default::default()))),
+    (Algorithm::Sha512_256, || Box::new(Sha512_256Engine(Default::default()))),
+    (Algorithm::Sha3_224, || Box::new(Sha3_224Engine(Default::default()))),
+    (Algorithm::Sha3_384, || Box::new(Sha3_384Engine(Default::default()))),
+    (Algorithm::Sha3_512, || Box::new(Sha3_512Engine(Default::default()))),
+    (Algorithm::Blake2b512, || Box::new(Blake2b512Engine(Default::default()))),
+    (Algorithm::Blake2s256, || Box::new(Blake2s256Engine(Default::default()))),
+    (Algorithm::Keccak256, || Box::new(Keccak256Engine(Default::default()))),
+    (Algorithm::Sha1, || Box::new(Sha1Engine(Default::default()))),
+    (Algorithm::Md5, || Box::new(Md5Engine(md5::Context::new()))),
+    (Algorithm::Crc32, || Box::new(Crc32Engine(crc32fast::Hasher::new()))),
+    (Algorithm::Crc32c, || Box::new(Crc32cEngine(0))),
+    (Algorithm::Adler32, || Box::new(Adler32Engine(adler2::Adler32::new()))),
+    (Algorithm::Xxh3_64, || Box::new(Xxh3_64Engine(Box::new(xxhash_rust::xxh3::Xxh3::new())))),
+    (Algorithm::Xxh3_128, || Box::new(Xxh3_128Engine(Box::new(xxhash_rust::xxh3::Xxh3::new())))),
+];
+
+/// All registered algorithms, in picker order.
+pub fn registry() -> &'static [AlgorithmInfo] {
+    static INFOS: OnceLock<Vec<AlgorithmInfo>> = OnceLock::new();
+    INFOS.get_or_init(|| {
+        REGISTRY
+            .iter()
+            .map(|(algorithm, create)| {
+                let engine = create();
+                AlgorithmInfo {
+                    algorithm: algorithm.clone(),
+                    name: engine.name(),
+                    aliases: engine.aliases(),
+                    description: engine.description(),
+                    output_len: engine.output_len(),
+                    integrity_only: !engine.is_cryptographic(),
+                    create: *create,
+                }
+            })
+            .collect()
+    })
+}
+
+pub fn info(algorithm: &Algorithm) -> &'static AlgorithmInfo {
+    registry()
+        .iter()
+        .find(|i| &i.algorithm == algorithm)
+        .expect("every Algorithm variant must be registered")
+}
+
+pub fn compute_hash_for_reader<R: Read>(reader: R, algorithm: &Algorithm) -> Result<String> {
+    let mut digests = compute_hashes_for_reader(reader, std::slice::from_ref(algorithm))?;
+    Ok(digests.remove(algorithm).unwrap_or_default())
+}
+
+/// Read `reader` once and feed every requested algorithm from the same buffer.
+/// Duplicate algorithms are hashed only once.
+pub fn compute_hashes_for_reader<R: Read>(reader: R, algorithms: &[Algorithm]) -> Result<BTreeMap<Algorithm, String>> {
+    compute_hashes_with_progress(reader, algorithms, &mut |_| {}, None)
+}
+
+/// Shared flag for stopping a running hash from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Error returned when a `CancelToken` stopped the hash; check for it with
+/// `err.is::<Cancelled>()`.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Like `compute_hashes_for_reader`, calling `progress` with the total number
+/// of bytes read so far after every chunk and failing with `Cancelled` as
+/// soon as `cancel` is triggered.
+pub fn compute_hashes_with_progress<R: Read>(
+    mut reader: R,
+    algorithms: &[Algorithm],
+    progress: &mut dyn FnMut(u64),
+    cancel: Option<&CancelToken>,
+) -> Result<BTreeMap<Algorithm, String>> {
+    let mut engines: BTreeMap<Algorithm, Box<dyn DigestEngine>> = algorithms
+        .iter()
+        .map(|a| (a.clone(), info(a).engine()))
+        .collect();
+    let mut buf = vec![0u8; READ_BUF_SIZE];
+    let mut read = 0u64;
+    loop {
+        if cancel.is_some_and(CancelToken::is_cancelled) {
+            return Err(Cancelled.into());
         }
-            Algorithm::Md5 => {
-            use md5::Context;
-            let mut ctx = Context::new();
-            let mut buf = [0u8; 64 * 1024];
-            loop {
-                let n = reader.read(&mut buf)?;
-                if n == 0 { break; }
-                ctx.consume(&buf[..n]);
-            }
-            Ok(format!("{:x}", ctx.finalize()))
+        let n = reader.read(&mut buf)?;
+        if n == 0 { break; }
+        for engine in engines.values_mut() {
+            engine.update(&buf[..n]);
         }
+        read += n as u64;
+        progress(read);
     }
+    Ok(engines
+        .into_iter()
+        .map(|(a, engine)| (a, hex::encode(engine.finalize())))
+        .collect())
 }
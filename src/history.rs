@@ -0,0 +1,137 @@
+use crate::models::{Algorithm, VerificationRecord, VerificationStatus};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Column the history is ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Date,
+    FileName,
+    Algorithm,
+    Status,
+}
+
+impl SortKey {
+    pub fn all() -> Vec<SortKey> {
+        vec![SortKey::Date, SortKey::FileName, SortKey::Algorithm, SortKey::Status]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortKey::Date => "Date",
+            SortKey::FileName => "File",
+            SortKey::Algorithm => "Algorithm",
+            SortKey::Status => "Status",
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Search, filter, sort and paging options for the verification history.
+/// The default matches everything, newest first.
+#[derive(Debug, Clone)]
+pub struct HistoryQuery {
+    /// Case-insensitive substring of the file name, path or any stored hash.
+    pub text: String,
+    pub status: Option<VerificationStatus>,
+    pub algorithm: Option<Algorithm>,
+    /// Inclusive local-time date bounds.
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub sort: SortKey,
+    pub descending: bool,
+    /// Zero-based page index.
+    pub page: usize,
+    pub page_size: usize,
+}
+
+impl Default for HistoryQuery {
+    fn default() -> Self {
+        HistoryQuery {
+            text: String::new(),
+            status: None,
+            algorithm: None,
+            from: None,
+            to: None,
+            sort: SortKey::Date,
+            descending: true,
+            page: 0,
+            page_size: 50,
+        }
+    }
+}
+
+/// One page of matching records, as indices into the slice passed to `query`.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryPage {
+    pub indices: Vec<usize>,
+    /// Matches across all pages.
+    pub total: usize,
+    /// The page actually returned, clamped to the last one.
+    pub page: usize,
+    pub page_count: usize,
+}
+
+fn local_day_start(date: NaiveDate) -> Option<DateTime<Utc>> {
+    let start = date.and_hms_opt(0, 0, 0)?;
+    Local.from_local_datetime(&start).earliest().map(|t| t.with_timezone(&Utc))
+}
+
+impl HistoryQuery {
+    pub fn matches(&self, rec: &VerificationRecord) -> bool {
+        if self.status.as_ref().is_some_and(|s| !s.same_kind(&rec.status))
+            || self.algorithm.as_ref().is_some_and(|a| a != &rec.algorithm)
+        {
+            return false;
+        }
+        if let Some(start) = self.from.and_then(local_day_start) {
+            if rec.timestamp < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.to.and_then(|d| d.succ_opt()).and_then(local_day_start) {
+            if rec.timestamp >= end {
+                return false;
+            }
+        }
+        let needle = self.text.trim().to_lowercase();
+        needle.is_empty()
+            || rec.file_name.to_lowercase().contains(&needle)
+            || rec.file_path.to_string_lossy().to_lowercase().contains(&needle)
+            || rec.computed_hash.contains(&needle)
+            || rec.reference_hash.as_deref().is_some_and(|h| h.to_lowercase().contains(&needle))
+            || rec.digests.values().any(|h| h.contains(&needle))
+    }
+
+    fn compare(&self, a: &VerificationRecord, b: &VerificationRecord) -> Ordering {
+        let ord = match self.sort {
+            SortKey::Date => a.timestamp.cmp(&b.timestamp),
+            SortKey::FileName => a.file_name.to_lowercase().cmp(&b.file_name.to_lowercase()),
+            SortKey::Algorithm => a.algorithm.name().cmp(b.algorithm.name()),
+            SortKey::Status => a.status.label().cmp(b.status.label()),
+        };
+        // Ties fall back to newest first so pages stay stable.
+        let ord = if self.descending { ord.reverse() } else { ord };
+        ord.then_with(|| b.timestamp.cmp(&a.timestamp))
+    }
+}
+
+/// Filter and sort `records`, returning the requested page.
+pub fn query(records: &[VerificationRecord], q: &HistoryQuery) -> HistoryPage {
+    let mut indices: Vec<usize> = (0..records.len()).filter(|&i| q.matches(&records[i])).collect();
+    indices.sort_by(|&a, &b| q.compare(&records[a], &records[b]));
+
+    let total = indices.len();
+    let page_size = q.page_size.max(1);
+    let page_count = total.div_ceil(page_size).max(1);
+    let page = q.page.min(page_count - 1);
+    let indices = indices.into_iter().skip(page * page_size).take(page_size).collect();
+    HistoryPage { indices, total, page, page_count }
+}
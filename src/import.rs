@@ -0,0 +1,363 @@
+//! Turn other tools' checksum logs into history records, so checks done
+//! before VeriFile (or alongside it) live in the same history and can be
+//! re-verified from there.
+//!
+//! Supported inputs:
+//! - `sha256sum -c` (and `md5sum`, `b2sum`, `b3sum`, ...) output: `<file>: OK`.
+//! - hashdeep listings (`%%%% HASHDEEP-1.0` header, CSV body).
+//! - rhash: `--check` logs, SFV listings and BSD/GNU hash listings.
+
+use crate::detect;
+use crate::encoding;
+use crate::manifest::{self, ChecksumFile, Diagnostic};
+use crate::models::{Algorithm, VerificationRecord, VerificationStatus};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Output of `sha256sum -c` and the other coreutils `*sum -c` tools.
+    ShaSumCheck,
+    Hashdeep,
+    Rhash,
+}
+
+impl ImportFormat {
+    pub fn all() -> Vec<ImportFormat> {
+        vec![ImportFormat::ShaSumCheck, ImportFormat::Hashdeep, ImportFormat::Rhash]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportFormat::ShaSumCheck => "sha256sum -c output",
+            ImportFormat::Hashdeep => "hashdeep",
+            ImportFormat::Rhash => "rhash",
+        }
+    }
+
+    /// Recognize a log from its content.
+    pub fn detect(text: &str) -> Option<ImportFormat> {
+        let mut lines = text.lines().map(str::trim_end).filter(|l| !l.trim().is_empty());
+        let first = lines.clone().next()?;
+        if first.starts_with("%%%% HASHDEEP") {
+            return Some(ImportFormat::Hashdeep);
+        }
+        if first.starts_with("; Generated by RHash") || text.lines().any(|l| l.starts_with("--( Verifying ")) {
+            return Some(ImportFormat::Rhash);
+        }
+        lines.any(|l| parse_check_line(l).is_some()).then_some(ImportFormat::ShaSumCheck)
+    }
+}
+
+/// Whether `text` is the output of a `-c` run (`sha256sum -c`, `rhash -c`),
+/// which gives verdicts but not digests. Records read from it only carry a
+/// digest when `ImportOptions::manifest` holds the checksum file it checked.
+pub fn is_check_log(text: &str) -> bool {
+    match ImportFormat::detect(text) {
+        Some(ImportFormat::ShaSumCheck) => true,
+        Some(ImportFormat::Rhash) => text.lines().any(|l| l.starts_with("--( Verifying ")),
+        _ => false,
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub struct ImportOptions {
+    /// Algorithm for logs that do not say which one was used.
+    pub algorithm: Algorithm,
+    /// Directory that relative file names in the log are resolved against.
+    pub base_dir: PathBuf,
+    /// Time given to records when the log carries none, e.g. its mtime.
+    pub default_time: DateTime<Utc>,
+    /// The checksum file a `-c` run checked, to recover the digests that
+    /// the check output itself leaves out.
+    pub manifest: Option<ChecksumFile>,
+}
+
+impl ImportOptions {
+    /// Defaults for the log at `path`: SHA-256, names relative to the log's
+    /// directory, no checksum file.
+    pub fn for_log(path: &Path) -> ImportOptions {
+        let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        ImportOptions {
+            algorithm: Algorithm::Sha256,
+            base_dir: std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf()),
+            default_time: Utc::now(),
+            manifest: None,
+        }
+    }
+}
+
+/// Records read from a log, oldest first, plus lines that were skipped.
+#[derive(Debug, Default)]
+pub struct Imported {
+    pub records: Vec<VerificationRecord>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parse a log in `format`.
+pub fn import_log(text: &str, format: ImportFormat, opts: &ImportOptions) -> Imported {
+    match format {
+        ImportFormat::ShaSumCheck => import_sum_check(text, opts),
+        ImportFormat::Hashdeep => import_hashdeep(text, opts),
+        ImportFormat::Rhash => import_rhash(text, opts),
+    }
+}
+
+/// Read and parse the log at `path`, detecting its format unless given.
+/// Relative names resolve against the log's directory and records without
+/// a timestamp get the log's modification time.
+pub fn import_file(path: &Path, format: Option<ImportFormat>, mut opts: ImportOptions) -> Result<Imported> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let format = match format.or_else(|| ImportFormat::detect(&text)) {
+        Some(f) => f,
+        None => anyhow::bail!("{} is not a log format VeriFile can import", path.display()),
+    };
+    if let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) {
+        opts.default_time = modified.into();
+    }
+    Ok(import_log(&text, format, &opts))
+}
+
+fn record(path: PathBuf, algorithm: Algorithm, status: VerificationStatus, timestamp: DateTime<Utc>) -> VerificationRecord {
+    VerificationRecord {
+        id: Uuid::new_v4().to_string(),
+        file_name: path.file_name().and_then(|s| s.to_str()).unwrap_or("file").to_string(),
+        file_path: path,
+        integrity_only: algorithm.is_integrity_only(),
+        algorithm,
+        computed_hash: String::new(),
+        reference_hash: None,
+        reference_encoding: None,
+        digests: BTreeMap::new(),
+        manifest: None,
+        metadata: None,
+        parent_id: None,
+        reverify: None,
+        prev_hash: None,
+        status,
+        timestamp,
+    }
+}
+
+/// `<file>: OK`, `<file>: FAILED` or `<file>: FAILED open or read`.
+fn parse_check_line(line: &str) -> Option<(&str, &str)> {
+    let (name, verdict) = line.rsplit_once(": ")?;
+    matches!(verdict, "OK" | "FAILED" | "FAILED open or read").then_some((name, verdict))
+}
+
+/// Status for a file a tool could not read, from the strerror text it printed.
+fn read_error_status(message: &str) -> VerificationStatus {
+    let kind = match message {
+        "No such file or directory" => return VerificationStatus::Missing,
+        "Permission denied" => "PermissionDenied",
+        "Is a directory" => "IsADirectory",
+        _ => "Other",
+    };
+    VerificationStatus::Error { kind: kind.to_string(), message: message.to_string() }
+}
+
+fn import_sum_check(text: &str, opts: &ImportOptions) -> Imported {
+    let mut out = Imported::default();
+    // "sha256sum: WARNING: ..." and "sha256sum: x: No such file" lines name the tool.
+    let tool_hint = text.lines().find_map(|l| {
+        let (tool, _) = l.split_once(": ")?;
+        tool.ends_with("sum").then(|| manifest::algorithm_hint_from_path(Path::new(tool)))?
+    });
+    // "sha256sum: x: Permission denied" explains a later "x: FAILED open or read".
+    let read_errors: BTreeMap<&str, &str> = text
+        .lines()
+        .filter_map(|l| {
+            let (tool, rest) = l.trim_end_matches('\r').split_once(": ")?;
+            tool.ends_with("sum").then(|| rest.rsplit_once(": "))?
+        })
+        .collect();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        let Some((name, verdict)) = parse_check_line(line) else {
+            if !line.trim().is_empty() && !line.contains("sum: ") {
+                out.diagnostics.push(Diagnostic { line: idx + 1, message: "not a check result".to_string() });
+            }
+            continue;
+        };
+        let entry = opts.manifest.as_ref().and_then(|m| m.entry_named(name));
+        let algorithm = entry
+            .and_then(|e| e.algorithm.clone())
+            .or_else(|| tool_hint.clone())
+            .unwrap_or_else(|| opts.algorithm.clone());
+        let ok = verdict == "OK";
+        let status = match verdict {
+            "OK" => VerificationStatus::Success,
+            "FAILED" => VerificationStatus::Failed,
+            _ => read_error_status(read_errors.get(name).copied().unwrap_or("could not open or read")),
+        };
+        let mut rec = record(opts.base_dir.join(name), algorithm, status, opts.default_time);
+        if let Some(e) = entry {
+            rec.reference_hash = Some(e.digest.clone());
+            if ok {
+                rec.computed_hash = hex_digest(&e.digest, &rec.algorithm).unwrap_or_default();
+            }
+        }
+        out.records.push(rec);
+    }
+    out
+}
+
+/// A listed digest in any encoding the checksum parser accepts (hex,
+/// base64, base32, SRI), as the lowercase hex the history stores.
+fn hex_digest(digest: &str, algorithm: &Algorithm) -> Option<String> {
+    encoding::decode_reference(digest)
+        .into_iter()
+        .filter(|d| d.algorithm.as_ref().is_none_or(|a| a == algorithm))
+        .find(|d| d.bytes.len() == algorithm.output_len())
+        .map(|d| hex::encode(d.bytes))
+}
+
+/// Preferred primary digest when a listing carries several.
+fn strongest(digests: &BTreeMap<Algorithm, String>) -> Option<Algorithm> {
+    let order = [Algorithm::Sha256, Algorithm::Sha1, Algorithm::Md5];
+    order.into_iter().find(|a| digests.contains_key(a)).or_else(|| digests.keys().next().cloned())
+}
+
+fn import_hashdeep(text: &str, opts: &ImportOptions) -> Imported {
+    let mut out = Imported::default();
+    let mut columns: Vec<Option<Algorithm>> = Vec::new();
+    let mut base = opts.base_dir.clone();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if let Some(header) = line.strip_prefix("%%%% ") {
+            if let Some(cols) = header.strip_prefix("size,") {
+                // Last column is the file name; tiger and whirlpool are skipped.
+                let cols: Vec<&str> = cols.split(',').collect();
+                columns = cols[..cols.len().saturating_sub(1)].iter().map(|c| c.parse().ok()).collect();
+            }
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix("##") {
+            if let Some(dir) = comment.trim().strip_prefix("Invoked from: ") {
+                base = PathBuf::from(dir.trim());
+            }
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.splitn(columns.len() + 2, ',').collect();
+        if columns.is_empty() || fields.len() != columns.len() + 2 {
+            out.diagnostics.push(Diagnostic { line: idx + 1, message: "does not match the hashdeep header".to_string() });
+            continue;
+        }
+        let digests: BTreeMap<Algorithm, String> = columns
+            .iter()
+            .zip(&fields[1..])
+            .filter_map(|(a, d)| Some((a.clone()?, d.to_ascii_lowercase())))
+            .collect();
+        let Some(algorithm) = strongest(&digests) else {
+            out.diagnostics.push(Diagnostic { line: idx + 1, message: "no supported digest".to_string() });
+            continue;
+        };
+        let mut rec = record(base.join(fields[fields.len() - 1]), algorithm.clone(), VerificationStatus::Unverified, opts.default_time);
+        rec.computed_hash = digests[&algorithm].clone();
+        if digests.len() > 1 {
+            rec.digests = digests;
+        }
+        out.records.push(rec);
+    }
+    out
+}
+
+/// The time in rhash's `; Generated by RHash v1.4.2 on 2023-01-15 at 10:12.33`.
+fn rhash_generated_at(line: &str) -> Option<DateTime<Utc>> {
+    let (_, when) = line.strip_prefix("; Generated by RHash")?.split_once(" on ")?;
+    let naive = NaiveDateTime::parse_from_str(when.trim(), "%Y-%m-%d at %H:%M.%S").ok()?;
+    Local.from_local_datetime(&naive).earliest().map(|t| t.with_timezone(&Utc))
+}
+
+fn import_rhash(text: &str, opts: &ImportOptions) -> Imported {
+    if text.lines().any(|l| l.starts_with("--( Verifying ")) {
+        return import_rhash_check(text, opts);
+    }
+    let timestamp = text.lines().find_map(rhash_generated_at).unwrap_or(opts.default_time);
+
+    // A hash listing: SFV (CRC32, "<file> <crc>") or BSD / GNU lines.
+    let sfv = text.lines().next().is_some_and(|l| l.starts_with("; Generated by RHash"));
+    let without_comments: String = text
+        .lines()
+        .map(|l| if l.starts_with(';') { "" } else { l })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let hint = sfv.then_some(Algorithm::Crc32);
+    let parsed = manifest::parse_checksum_text(&without_comments, hint.as_ref());
+    let mut out = Imported { records: Vec::new(), diagnostics: parsed.diagnostics };
+    for entry in parsed.entries {
+        let Some(name) = &entry.file_name else {
+            out.diagnostics.push(Diagnostic { line: entry.line, message: "digest without a file name".to_string() });
+            continue;
+        };
+        let candidates = detect::candidate_algorithms(&entry.digest);
+        let algorithm = entry
+            .algorithm
+            .clone()
+            .or_else(|| candidates.contains(&opts.algorithm).then(|| opts.algorithm.clone()))
+            .or_else(|| candidates.first().cloned())
+            .unwrap_or_else(|| opts.algorithm.clone());
+        let Some(digest) = hex_digest(&entry.digest, &algorithm) else {
+            out.diagnostics.push(Diagnostic { line: entry.line, message: "digest does not fit the algorithm".to_string() });
+            continue;
+        };
+        let mut rec = record(opts.base_dir.join(name), algorithm, VerificationStatus::Unverified, timestamp);
+        rec.computed_hash = digest;
+        out.records.push(rec);
+    }
+    out
+}
+
+/// `rhash -c` output: a `--( Verifying <file> )--` banner, then one
+/// `<file>   OK|ERR|<error>` line per entry and a summary.
+fn import_rhash_check(text: &str, opts: &ImportOptions) -> Imported {
+    let mut out = Imported::default();
+    let mut in_block = false;
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r').trim_end();
+        if line.starts_with("--( Verifying ") {
+            in_block = true;
+            continue;
+        }
+        if line.starts_with("-----") {
+            in_block = false;
+            continue;
+        }
+        if !in_block || line.is_empty() {
+            continue;
+        }
+        // Names are padded to a column, so the verdict follows a run of spaces.
+        let Some((name, verdict)) = line.split_once("  ").map(|(n, v)| (n.trim(), v.trim())) else {
+            out.diagnostics.push(Diagnostic { line: idx + 1, message: "not a check result".to_string() });
+            continue;
+        };
+        let status = match verdict {
+            "OK" => VerificationStatus::Success,
+            "ERR" => VerificationStatus::Failed,
+            error => read_error_status(error),
+        };
+        let entry = opts.manifest.as_ref().and_then(|m| m.entry_named(name));
+        let algorithm = entry.and_then(|e| e.algorithm.clone()).unwrap_or_else(|| opts.algorithm.clone());
+        let mut rec = record(opts.base_dir.join(name), algorithm, status, opts.default_time);
+        if let Some(e) = entry {
+            rec.reference_hash = Some(e.digest.clone());
+            if verdict == "OK" {
+                rec.computed_hash = hex_digest(&e.digest, &rec.algorithm).unwrap_or_default();
+            }
+        }
+        out.records.push(rec);
+    }
+    out
+}
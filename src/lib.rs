@@ -0,0 +1,35 @@
+//! File hashing and verification shared by the VeriFile GUI and CLI.
+//!
+//! - [`models`]: [`Algorithm`], [`VerificationRecord`] and friends.
+//! - [`hashers`]: streaming digest engines and the algorithm registry.
+//! - [`file_ops`]: hashing and verifying files on disk.
+//! - [`encoding`] / [`detect`]: decoding reference hashes and guessing their algorithm.
+//! - [`manifest`]: parsing, verifying and generating checksum files.
+//! - [`progress`]: throughput and ETA for long-running hashes.
+//! - [`storage`]: the verification history; [`history`] searches it and
+//!   [`export`] writes it out as CSV, JSON Lines or an HTML report.
+//! - [`import`]: reading `sha256sum -c`, hashdeep and rhash logs into the history.
+//! - [`audit`]: the hash chain that makes edits to the history detectable.
+//!
+//! ```no_run
+//! use verifile::{file_ops, Algorithm};
+//!
+//! let rec = file_ops::verify_file("image.iso".as_ref(), &Algorithm::Sha256, &[], Some("sha256:..."))?;
+//! println!("{:?} {}", rec.status, rec.computed_hash);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod audit;
+pub mod detect;
+pub mod encoding;
+pub mod export;
+pub mod file_ops;
+pub mod hashers;
+pub mod history;
+pub mod import;
+pub mod manifest;
+pub mod models;
+pub mod progress;
+pub mod storage;
+
+pub use models::{Algorithm, HashEncoding, VerificationRecord, VerificationStatus};
@@ -1,18 +1,24 @@
+mod cli;
+#[cfg(feature = "gui")]
 mod gui;
-mod hashers;
-mod file_ops;
-mod models;
-mod storage;
-mod utils;
 
+use clap::Parser;
+#[cfg(feature = "gui")]
 use iced::{window, Size};
 
+#[cfg(feature = "gui")]
 fn main() -> iced::Result {
+    let args = cli::Cli::parse();
+    if let Some(command) = args.command {
+        run_command(command, args.json);
+    }
+
     iced::application(
         "VeriFILE - File Verifier",
         gui::VeriFileApp::update,
         gui::VeriFileApp::view,
     )
+    .subscription(gui::VeriFileApp::subscription)
     .window(window::Settings {
         size: Size::new(1200.0, 760.0),
         resizable: true,
@@ -20,3 +26,27 @@ fn main() -> iced::Result {
     })
     .run_with(gui::VeriFileApp::new)
 }
+
+/// Without the GUI there is nothing to start, so a subcommand is required.
+#[cfg(not(feature = "gui"))]
+fn main() {
+    let args = cli::Cli::parse();
+    match args.command {
+        Some(command) => run_command(command, args.json),
+        None => {
+            let _ = <cli::Cli as clap::CommandFactory>::command().print_help();
+            std::process::exit(cli::EXIT_ERROR);
+        }
+    }
+}
+
+fn run_command(command: cli::Command, json: bool) -> ! {
+    let code = match cli::run(command, json) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("verifile: {:#}", e);
+            cli::EXIT_ERROR
+        }
+    };
+    std::process::exit(code);
+}
@@ -0,0 +1,670 @@
+use crate::detect;
+use crate::encoding;
+use crate::file_ops;
+use crate::hashers::{self, CancelToken};
+use crate::models::{Algorithm, ManifestSummary, VerificationRecord, VerificationStatus};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// One `(algorithm hint, filename, digest)` line of a checksum file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChecksumEntry {
+    /// 1-based line number in the source text.
+    pub line: usize,
+    /// Algorithm named by a BSD tag line or implied by the checksum file name.
+    pub algorithm: Option<Algorithm>,
+    /// `None` for a bare digest with no filename.
+    pub file_name: Option<String>,
+    pub digest: String,
+    /// GNU `*` marker: the file was hashed in binary mode.
+    pub binary: bool,
+}
+
+/// A line that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChecksumFile {
+    pub entries: Vec<ChecksumEntry>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ChecksumFile {
+    /// The entry listed under exactly `file_name`, matched first on the full
+    /// listed name and then on its last path component.
+    pub fn entry_named(&self, file_name: &str) -> Option<&ChecksumEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.file_name.as_deref() == Some(file_name))
+            .or_else(|| {
+                self.entries.iter().find(|e| {
+                    e.file_name
+                        .as_deref()
+                        .and_then(|n| Path::new(n).file_name())
+                        .is_some_and(|n| n == file_name)
+                })
+            })
+    }
+
+    /// Like [`entry_named`](Self::entry_named), but a file with a single
+    /// entry is returned as-is, so a renamed download still finds its digest.
+    pub fn entry_for(&self, file_name: &str) -> Option<&ChecksumEntry> {
+        self.entry_named(file_name).or(match self.entries.as_slice() {
+            [only] => Some(only),
+            _ => None,
+        })
+    }
+}
+
+/// Guess the algorithm from a checksum file's name, e.g. `SHA256SUMS`,
+/// `foo.iso.sha512`, `B2SUMS` or `bar.md5`.
+pub fn algorithm_hint_from_path(path: &Path) -> Option<Algorithm> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    let stem = if let Some(stem) = name.strip_suffix("sums") {
+        stem
+    } else if let Some(stem) = name.strip_suffix("sum") {
+        stem
+    } else {
+        name.rsplit('.').next()?
+    };
+    let stem = stem.trim_end_matches(['.', '-', '_']);
+    match stem {
+        "b2" => Some(Algorithm::Blake2b512),
+        _ => stem.parse().ok(),
+    }
+}
+
+/// Whether `path` is named like a checksum file (`SHA256SUMS`, `x.iso.md5`,
+/// `checksums.txt`) rather than data. Judged by name only.
+pub fn looks_like_checksum_file(path: &Path) -> bool {
+    if algorithm_hint_from_path(path).is_some() {
+        return true;
+    }
+    let name = path.file_name().map(|n| n.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+    let name = name.strip_suffix(".txt").unwrap_or(&name);
+    name.ends_with("sums") || name.ends_with(".sum") || name.contains("checksum")
+}
+
+/// Parse GNU coreutils (`<digest>  <file>`, `<digest> *<file>`, backslash-escaped
+/// names) and BSD tag (`SHA256 (<file>) = <digest>`) checksum files.
+/// A line holding a single digest is accepted as an entry without a filename.
+pub fn parse_checksum_text(text: &str, hint: Option<&Algorithm>) -> ChecksumFile {
+    let mut out = ChecksumFile::default();
+    for (idx, raw) in text.lines().enumerate() {
+        let line = idx + 1;
+        let t = raw.trim_end_matches('\r');
+        if t.trim().is_empty() || t.trim_start().starts_with('#') {
+            continue;
+        }
+        match parse_line(t, hint) {
+            Ok(mut entry) => {
+                entry.line = line;
+                out.entries.push(entry);
+            }
+            Err(message) => out.diagnostics.push(Diagnostic { line, message }),
+        }
+    }
+    out
+}
+
+fn parse_line(t: &str, hint: Option<&Algorithm>) -> Result<ChecksumEntry, String> {
+    let (escaped, body) = match t.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, t),
+    };
+
+    if let Some(entry) = parse_bsd_tag(body, escaped)? {
+        return Ok(entry);
+    }
+    parse_gnu_line(body, escaped, hint).map_err(|e| match split_bsd_tag(body) {
+        Some((label, _, _)) => format!("unknown algorithm '{}'", label),
+        None => e,
+    })
+}
+
+/// `<digest>  <file>`, `<digest> *<file>`, a bare digest or the legacy
+/// `<file> <digest>` layout.
+fn parse_gnu_line(body: &str, escaped: bool, hint: Option<&Algorithm>) -> Result<ChecksumEntry, String> {
+    let body = body.trim_start();
+    let (digest, rest) = match body.split_once(' ') {
+        Some(parts) => parts,
+        None => {
+            check_digest(body, hint)?;
+            return Ok(ChecksumEntry {
+                line: 0,
+                algorithm: hint.cloned(),
+                file_name: None,
+                digest: body.to_string(),
+                binary: false,
+            });
+        }
+    };
+
+    if check_digest(digest, hint).is_err() {
+        // Legacy "<file> <digest>" layout, unless it is a tag line with a
+        // label we do not know.
+        if let Some((name, last)) = body.rsplit_once(char::is_whitespace).filter(|_| split_bsd_tag(body).is_none()) {
+            if check_digest(last, hint).is_ok() {
+                return Ok(ChecksumEntry {
+                    line: 0,
+                    algorithm: hint.cloned(),
+                    file_name: Some(name.trim().to_string()),
+                    digest: last.to_string(),
+                    binary: false,
+                });
+            }
+        }
+        check_digest(digest, hint)?;
+    }
+
+    let (binary, name) = if let Some(name) = rest.strip_prefix('*') {
+        (true, name)
+    } else if let Some(name) = rest.strip_prefix(' ') {
+        (false, name)
+    } else {
+        (false, rest)
+    };
+    if name.is_empty() {
+        return Err("missing file name".to_string());
+    }
+    Ok(ChecksumEntry {
+        line: 0,
+        algorithm: hint.cloned(),
+        file_name: Some(if escaped { unescape(name)? } else { name.to_string() }),
+        digest: digest.to_string(),
+        binary,
+    })
+}
+
+/// `ALG (file) = digest`; returns `Ok(None)` if the line is not in tag form
+/// or the label is not an algorithm, as in a GNU line for `report (1) = x.pdf`.
+fn parse_bsd_tag(body: &str, escaped: bool) -> Result<Option<ChecksumEntry>, String> {
+    let Some((label, name, digest)) = split_bsd_tag(body) else {
+        return Ok(None);
+    };
+    let Ok(algorithm) = label.parse::<Algorithm>() else {
+        return Ok(None);
+    };
+    check_digest(digest, Some(&algorithm))?;
+    Ok(Some(ChecksumEntry {
+        line: 0,
+        algorithm: Some(algorithm),
+        file_name: Some(if escaped { unescape(name)? } else { name.to_string() }),
+        digest: digest.to_string(),
+        binary: false,
+    }))
+}
+
+/// The label, file name and digest of a line shaped like `ALG (file) = digest`.
+fn split_bsd_tag(body: &str) -> Option<(&str, &str, &str)> {
+    let (open, close) = (body.find(" (")?, body.rfind(") = ")?);
+    let label = &body[..open];
+    if close < open || label.is_empty() || label.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label, &body[open + 2..close], body[close + 4..].trim()))
+}
+
+fn check_digest(digest: &str, algorithm: Option<&Algorithm>) -> Result<(), String> {
+    let fits = encoding::decode_reference(digest)
+        .iter()
+        .any(|d| algorithm.is_none_or(|a| a.output_len() == d.bytes.len()));
+    if fits {
+        Ok(())
+    } else if let Some(a) = algorithm {
+        Err(format!("'{}' is not a valid {} digest", digest, a.name()))
+    } else {
+        Err(format!("'{}' is not a recognized digest", digest))
+    }
+}
+
+/// Undo coreutils filename escaping (`\\`, `\n`, `\r`).
+fn unescape(name: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => out.push('\\'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            other => return Err(format!("invalid escape sequence '\\{}'", other.map(String::from).unwrap_or_default())),
+        }
+    }
+    Ok(out)
+}
+
+
+/// Outcome for one manifest entry, mirroring `sha256sum -c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryStatus {
+    Ok,
+    Failed,
+    Missing,
+}
+
+impl EntryStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryStatus::Ok => "OK",
+            EntryStatus::Failed => "FAILED",
+            EntryStatus::Missing => "MISSING",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EntryResult {
+    pub entry: ChecksumEntry,
+    /// Entry path resolved against the manifest's directory.
+    pub path: PathBuf,
+    /// Algorithm whose digest matched, or the first one tried.
+    pub algorithm: Option<Algorithm>,
+    pub computed_hash: Option<String>,
+    pub status: EntryStatus,
+    /// Read error behind a `Failed` status, if any.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestReport {
+    pub manifest_path: PathBuf,
+    /// Digest of the manifest file itself, with `algorithm`.
+    pub manifest_digest: String,
+    pub algorithm: Algorithm,
+    pub results: Vec<EntryResult>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ManifestReport {
+    pub fn summary(&self) -> ManifestSummary {
+        let count = |s: EntryStatus| self.results.iter().filter(|r| r.status == s).count();
+        ManifestSummary {
+            total: self.results.len(),
+            ok: count(EntryStatus::Ok),
+            failed: count(EntryStatus::Failed),
+            missing: count(EntryStatus::Missing),
+        }
+    }
+
+    /// One aggregate history record for the whole manifest check.
+    pub fn history_record(&self) -> VerificationRecord {
+        let summary = self.summary();
+        let status = if summary.total > 0 && summary.ok == summary.total {
+            VerificationStatus::Success
+        } else {
+            VerificationStatus::Failed
+        };
+        VerificationRecord {
+            id: Uuid::new_v4().to_string(),
+            file_name: self.manifest_path.file_name().and_then(|s| s.to_str()).unwrap_or("manifest").to_string(),
+            file_path: self.manifest_path.clone(),
+            algorithm: self.algorithm.clone(),
+            computed_hash: self.manifest_digest.clone(),
+            reference_hash: None,
+            reference_encoding: None,
+            digests: Default::default(),
+            integrity_only: self.algorithm.is_integrity_only(),
+            manifest: Some(summary),
+            metadata: None,
+            parent_id: None,
+            reverify: None,
+            prev_hash: None,
+            status,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Check every entry of the checksum file at `manifest_path`, resolving
+/// names relative to the manifest's directory and hashing files in parallel.
+///
+/// Entries without an algorithm hint are matched against every candidate
+/// algorithm for their digest length.
+pub fn verify_manifest(manifest_path: &Path) -> Result<ManifestReport> {
+    verify_manifest_with_progress(manifest_path, &mut |_| {}, None)
+}
+
+/// `verify_manifest`, calling `progress` with the bytes hashed so far across
+/// all listed files and failing with `Cancelled` once `cancel` is triggered.
+pub fn verify_manifest_with_progress(
+    manifest_path: &Path,
+    progress: &mut dyn FnMut(u64),
+    cancel: Option<&CancelToken>,
+) -> Result<ManifestReport> {
+    let text = std::fs::read_to_string(manifest_path)?;
+    let base = manifest_path.parent().unwrap_or(Path::new("."));
+    verify_text(&text, manifest_path, base, progress, cancel)
+}
+
+/// Like `verify_manifest`, for manifest text that did not come from a file on
+/// disk (e.g. stdin). Entries are resolved against `base`.
+pub fn verify_manifest_text(text: &str, manifest_path: &Path, base: &Path) -> Result<ManifestReport> {
+    verify_text(text, manifest_path, base, &mut |_| {}, None)
+}
+
+/// Total size of the files the checksum file at `manifest_path` lists, for
+/// sizing a progress bar. Missing files count as empty.
+pub fn listed_size(manifest_path: &Path) -> Option<u64> {
+    let text = std::fs::read_to_string(manifest_path).ok()?;
+    let base = manifest_path.parent().unwrap_or(Path::new("."));
+    let parsed = parse_checksum_text(&text, algorithm_hint_from_path(manifest_path).as_ref());
+    Some(
+        parsed
+            .entries
+            .iter()
+            .filter_map(|e| std::fs::metadata(base.join(e.file_name.as_deref()?)).ok())
+            .map(|m| m.len())
+            .sum(),
+    )
+}
+
+/// How often a running manifest check passes its byte count to `progress`.
+const PROGRESS_POLL: Duration = Duration::from_millis(20);
+
+fn verify_text(
+    text: &str,
+    manifest_path: &Path,
+    base: &Path,
+    progress: &mut dyn FnMut(u64),
+    cancel: Option<&CancelToken>,
+) -> Result<ManifestReport> {
+    let hint = algorithm_hint_from_path(manifest_path);
+    let parsed = parse_checksum_text(text, hint.as_ref());
+
+    let mut diagnostics = parsed.diagnostics;
+    let mut entries = Vec::new();
+    for entry in parsed.entries {
+        if entry.file_name.is_some() {
+            entries.push(entry);
+        } else {
+            diagnostics.push(Diagnostic { line: entry.line, message: "digest without a file name".to_string() });
+        }
+    }
+
+    // Files are hashed on the rayon pool while this thread reports the
+    // running total, since `progress` cannot be shared between threads.
+    let hashed = AtomicU64::new(0);
+    let results = std::thread::scope(|scope| {
+        let worker = scope.spawn(|| {
+            entries
+                .into_par_iter()
+                .map(|entry| check_entry(base, entry, &hashed, cancel))
+                .collect::<Result<Vec<EntryResult>>>()
+        });
+        while !worker.is_finished() {
+            progress(hashed.load(Ordering::Relaxed));
+            std::thread::sleep(PROGRESS_POLL);
+        }
+        progress(hashed.load(Ordering::Relaxed));
+        worker.join().expect("manifest worker panicked")
+    })?;
+
+    let algorithm = hint
+        .or_else(|| results.iter().find_map(|r| r.algorithm.clone()))
+        .unwrap_or(Algorithm::Sha256);
+    let manifest_digest = hashers::compute_hash_for_reader(text.as_bytes(), &algorithm)?;
+
+    Ok(ManifestReport {
+        manifest_path: manifest_path.to_path_buf(),
+        manifest_digest,
+        algorithm,
+        results,
+        diagnostics,
+    })
+}
+
+/// Hash one listed file, adding the bytes read to `hashed`. Only
+/// cancellation is an error; a file that cannot be read is reported in the
+/// result.
+fn check_entry(base: &Path, entry: ChecksumEntry, hashed: &AtomicU64, cancel: Option<&CancelToken>) -> Result<EntryResult> {
+    let path = base.join(entry.file_name.as_deref().unwrap_or_default());
+    let candidates = match &entry.algorithm {
+        Some(a) => vec![a.clone()],
+        None => detect::candidate_algorithms(&entry.digest),
+    };
+    let mut result = EntryResult {
+        entry,
+        path,
+        algorithm: candidates.first().cloned(),
+        computed_hash: None,
+        status: EntryStatus::Failed,
+        error: None,
+    };
+    if !result.path.is_file() {
+        result.status = EntryStatus::Missing;
+        return Ok(result);
+    }
+    let mut read = 0;
+    let mut progress = |n: u64| {
+        hashed.fetch_add(n - read, Ordering::Relaxed);
+        read = n;
+    };
+    match file_ops::compute_file_hashes_with_progress(&result.path, &candidates, &mut progress, cancel) {
+        Ok(digests) => {
+            let matched = digests
+                .iter()
+                .find(|(a, h)| detect::match_reference(&result.entry.digest, a, h).is_some());
+            if let Some((a, h)) = matched {
+                result.algorithm = Some(a.clone());
+                result.computed_hash = Some(h.clone());
+                result.status = EntryStatus::Ok;
+            } else if let Some(a) = &result.algorithm {
+                result.computed_hash = digests.get(a).cloned();
+            }
+        }
+        Err(e) if e.is::<hashers::Cancelled>() => return Err(e),
+        Err(e) => result.error = Some(e.to_string()),
+    }
+    Ok(result)
+}
+
+
+/// Output layout for generated manifests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    /// `sha256sum` style: `<digest>  <file>`.
+    Gnu,
+    /// `SHA256 (<file>) = <digest>`.
+    BsdTag,
+    /// `b3sum` output; always BLAKE3, GNU layout.
+    B3sum,
+    Json,
+}
+
+impl ManifestFormat {
+    pub fn all() -> Vec<ManifestFormat> {
+        vec![ManifestFormat::Gnu, ManifestFormat::BsdTag, ManifestFormat::B3sum, ManifestFormat::Json]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ManifestFormat::Gnu => "GNU (sha256sum)",
+            ManifestFormat::BsdTag => "BSD tag",
+            ManifestFormat::B3sum => "b3sum",
+            ManifestFormat::Json => "JSON",
+        }
+    }
+
+    /// Conventional file name for a manifest in this format, e.g. `SHA256SUMS`.
+    pub fn default_file_name(&self, algorithm: &Algorithm) -> String {
+        match self {
+            ManifestFormat::Gnu | ManifestFormat::BsdTag => format!("{}SUMS", tag_label(algorithm).to_ascii_uppercase()),
+            ManifestFormat::B3sum => "B3SUMS".to_string(),
+            ManifestFormat::Json => "checksums.json".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ManifestFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Algorithm label used in BSD tag lines, following coreutils where it has one.
+fn tag_label(algorithm: &Algorithm) -> &'static str {
+    match algorithm {
+        Algorithm::Md5 => "MD5",
+        Algorithm::Sha1 => "SHA1",
+        Algorithm::Sha224 => "SHA224",
+        Algorithm::Sha256 => "SHA256",
+        Algorithm::Sha384 => "SHA384",
+        Algorithm::Sha512 => "SHA512",
+        Algorithm::Sha512_256 => "SHA512t256",
+        Algorithm::Blake2b512 => "BLAKE2b",
+        other => other.name(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    pub algorithm: Algorithm,
+    /// Globs matched against each file's path relative to its input root.
+    /// Empty means every file.
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Write absolute paths instead of paths relative to each input root.
+    pub absolute_paths: bool,
+}
+
+/// One generated manifest line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManifestLine {
+    pub path: String,
+    pub digest: String,
+}
+
+fn build_globset(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for p in patterns {
+        builder.add(Glob::new(p).with_context(|| format!("invalid glob '{}'", p))?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+/// Manifest paths always use `/`, whatever the platform.
+fn manifest_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Expand `inputs` (files and directories, walked recursively) into the
+/// files to hash, as `(path on disk, path to write)`, sorted by written path.
+/// A file reached through more than one input is listed once; two different
+/// files that would be written under the same name are an error.
+pub fn collect_files(inputs: &[PathBuf], opts: &GenerateOptions) -> Result<Vec<(PathBuf, String)>> {
+    let include = build_globset(&opts.include)?;
+    let exclude = build_globset(&opts.exclude)?;
+    let mut files = Vec::new();
+    for input in inputs {
+        let (root, walk) = if input.is_dir() {
+            (input.clone(), WalkDir::new(input).sort_by_file_name())
+        } else {
+            (input.parent().map(Path::to_path_buf).unwrap_or_default(), WalkDir::new(input))
+        };
+        for entry in walk {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let rel = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+            if include.as_ref().is_some_and(|g| !g.is_match(rel)) || exclude.as_ref().is_some_and(|g| g.is_match(rel)) {
+                continue;
+            }
+            let written = if opts.absolute_paths {
+                std::path::absolute(entry.path())?.to_string_lossy().into_owned()
+            } else {
+                manifest_path(rel)
+            };
+            files.push((entry.path().to_path_buf(), written));
+        }
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    let mut unique: Vec<(PathBuf, String)> = Vec::with_capacity(files.len());
+    for (disk, written) in files {
+        if let Some((prev, prev_written)) = unique.last() {
+            if *prev_written == written {
+                if same_file(prev, &disk) {
+                    continue;
+                }
+                anyhow::bail!("{} and {} would both be listed as {}", prev.display(), disk.display(), written);
+            }
+        }
+        unique.push((disk, written));
+    }
+    Ok(unique)
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Hash the files selected by `inputs` and `opts`, in deterministic order.
+pub fn generate_manifest(inputs: &[PathBuf], opts: &GenerateOptions) -> Result<Vec<ManifestLine>> {
+    collect_files(inputs, opts)?
+        .into_par_iter()
+        .map(|(disk, path)| {
+            let digest = file_ops::compute_file_hash(&disk, &opts.algorithm)
+                .with_context(|| format!("failed to hash {}", disk.display()))?;
+            Ok(ManifestLine { path, digest })
+        })
+        .collect()
+}
+
+/// Render generated lines in `format`. `B3sum` ignores `algorithm`; callers
+/// should have hashed with BLAKE3.
+pub fn render_manifest(lines: &[ManifestLine], algorithm: &Algorithm, format: ManifestFormat) -> Result<String> {
+    if format == ManifestFormat::Json {
+        #[derive(Serialize)]
+        struct JsonManifest<'a> {
+            algorithm: &'a str,
+            files: &'a [ManifestLine],
+        }
+        let mut s = serde_json::to_string_pretty(&JsonManifest { algorithm: algorithm.name(), files: lines })?;
+        s.push('\n');
+        return Ok(s);
+    }
+    let mut out = String::new();
+    for line in lines {
+        let needs_escape = line.path.contains(['\\', '\n', '\r']);
+        let name = if needs_escape { escape(&line.path) } else { line.path.clone() };
+        if needs_escape {
+            out.push('\\');
+        }
+        match format {
+            ManifestFormat::BsdTag => out.push_str(&format!("{} ({}) = {}\n", tag_label(algorithm), name, line.digest)),
+            _ => out.push_str(&format!("{}  {}\n", line.digest, name)),
+        }
+    }
+    Ok(out)
+}
+
+/// Inverse of `unescape`.
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
@@ -1,29 +1,57 @@
 use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::fmt;
+use std::str::FromStr;
+use crate::hashers;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Algorithm {
     Blake3,
     Sha256,
     Sha512,
     Sha3_256,
     Md5,
+    Sha1,
+    Sha224,
+    Sha384,
+    Sha512_256,
+    Sha3_224,
+    Sha3_384,
+    Sha3_512,
+    Blake2b512,
+    Blake2s256,
+    Keccak256,
+    Crc32,
+    Crc32c,
+    Adler32,
+    Xxh3_64,
+    Xxh3_128,
 }
 
 impl Algorithm {
+    /// All algorithms known to the hasher registry, in picker order.
     pub fn all() -> Vec<Algorithm> {
-        vec![Algorithm::Blake3, Algorithm::Sha256, Algorithm::Sha512, Algorithm::Sha3_256, Algorithm::Md5]
+        hashers::registry().iter().map(|i| i.algorithm.clone()).collect()
     }
 
     pub fn name(&self) -> &'static str {
-        match self {
-            Algorithm::Blake3 => "BLAKE3",
-            Algorithm::Sha256 => "SHA-256",
-            Algorithm::Sha512 => "SHA-512",
-            Algorithm::Sha3_256 => "SHA3-256",
-            Algorithm::Md5 => "MD5",
-        }
+        hashers::info(self).name
+    }
+
+    pub fn description(&self) -> &'static str {
+        hashers::info(self).description
+    }
+
+    /// True for checksums that detect accidental corruption but offer no
+    /// protection against deliberate tampering (CRC, Adler, xxHash).
+    pub fn is_integrity_only(&self) -> bool {
+        hashers::info(self).integrity_only
+    }
+
+    /// Digest length in bytes.
+    pub fn output_len(&self) -> usize {
+        hashers::info(self).output_len
     }
 }
 
@@ -33,11 +61,247 @@ impl fmt::Display for Algorithm {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl FromStr for Algorithm {
+    type Err = anyhow::Error;
+
+    /// Accepts the display name or any registered alias, e.g. "SHA-256" or "sha256".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        hashers::registry()
+            .iter()
+            .find(|i| i.matches_name(s))
+            .map(|i| i.algorithm.clone())
+            .ok_or_else(|| anyhow::anyhow!("unknown hash algorithm: {}", s))
+    }
+}
+
+/// How a reference hash was written when it was supplied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashEncoding {
+    Hex,
+    Base64,
+    Base32,
+    NixBase32,
+    /// Subresource Integrity, e.g. `sha256-<base64>`.
+    Sri,
+    /// Self-describing IPFS multihash or CID.
+    Multihash,
+}
+
+impl HashEncoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashEncoding::Hex => "hex",
+            HashEncoding::Base64 => "base64",
+            HashEncoding::Base32 => "base32",
+            HashEncoding::NixBase32 => "Nix base32",
+            HashEncoding::Sri => "SRI",
+            HashEncoding::Multihash => "multihash",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VerificationStatus {
+    /// The digest matched the reference hash.
     Success,
+    /// The digest did not match the reference hash.
     Failed,
+    /// Hashed, but there was no reference hash to compare against.
+    Unverified,
+    /// Nothing exists at the file's path.
+    Missing,
+    /// The file could not be read. `kind` names the I/O error kind, e.g.
+    /// "PermissionDenied", or is "Other".
+    Error { kind: String, message: String },
     InProgress,
+    /// Stopped by the user before the hash finished.
+    Cancelled,
+}
+
+impl VerificationStatus {
+    /// One value per status, in filter order. `Error` appears with empty
+    /// fields and stands for any error; compare with `same_kind`.
+    pub fn all() -> Vec<VerificationStatus> {
+        vec![
+            VerificationStatus::Success,
+            VerificationStatus::Failed,
+            VerificationStatus::Unverified,
+            VerificationStatus::Missing,
+            VerificationStatus::Error { kind: String::new(), message: String::new() },
+            VerificationStatus::InProgress,
+            VerificationStatus::Cancelled,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            VerificationStatus::Success => "Success",
+            VerificationStatus::Failed => "Failed",
+            VerificationStatus::Unverified => "Unverified",
+            VerificationStatus::Missing => "Missing",
+            VerificationStatus::Error { .. } => "Error",
+            VerificationStatus::InProgress => "In progress",
+            VerificationStatus::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Same variant, ignoring the details of an `Error`.
+    pub fn same_kind(&self, other: &VerificationStatus) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// The status for a file that could not be hashed: `Missing` when it
+    /// does not exist, otherwise `Error` with the underlying I/O error kind.
+    pub fn from_error(err: &anyhow::Error) -> VerificationStatus {
+        let io = err.chain().find_map(|e| e.downcast_ref::<std::io::Error>());
+        match io.map(|e| e.kind()) {
+            Some(std::io::ErrorKind::NotFound) => VerificationStatus::Missing,
+            kind => VerificationStatus::Error {
+                kind: kind.map_or_else(|| "Other".to_string(), |k| format!("{:?}", k)),
+                message: format!("{:#}", err),
+            },
+        }
+    }
+}
+
+impl fmt::Display for VerificationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for VerificationStatus {
+    type Err = anyhow::Error;
+
+    /// Case-insensitive label, e.g. "failed" or "in-progress".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let norm = |x: &str| x.to_ascii_lowercase().replace([' ', '-', '_'], "");
+        VerificationStatus::all()
+            .into_iter()
+            .find(|v| norm(v.label()) == norm(s.trim()))
+            .ok_or_else(|| anyhow::anyhow!("unknown status: {}", s))
+    }
+}
+
+/// What re-hashing a previously verified file found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReverifyOutcome {
+    /// Same digest as the original record.
+    Unchanged,
+    /// The digest differs and so does the file's size or modification
+    /// time: it was edited or replaced.
+    Changed,
+    /// The digest differs but size and modification time are as recorded:
+    /// the content changed without a write, e.g. silent bit rot.
+    Corrupted,
+    /// Nothing exists at the recorded path any more.
+    Missing,
+}
+
+impl ReverifyOutcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReverifyOutcome::Unchanged => "Unchanged",
+            ReverifyOutcome::Changed => "Changed",
+            ReverifyOutcome::Corrupted => "Corrupted",
+            ReverifyOutcome::Missing => "Missing",
+        }
+    }
+}
+
+/// Filesystem metadata captured when a file was hashed. Fields the
+/// platform does not expose are `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetadata {
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inode: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<u64>,
+    /// Unix permission bits, e.g. `0o644`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub readonly: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+}
+
+impl FileMetadata {
+    /// What differs from `earlier`, e.g. `["size", "modification time"]`.
+    pub fn changes_since(&self, earlier: &FileMetadata) -> Vec<&'static str> {
+        let mut changes = Vec::new();
+        if self.size != earlier.size {
+            changes.push("size");
+        }
+        if self.modified != earlier.modified {
+            changes.push("modification time");
+        }
+        if (self.inode, self.device) != (earlier.inode, earlier.device) {
+            changes.push("inode");
+        }
+        if (self.mode, self.readonly) != (earlier.mode, earlier.readonly) {
+            changes.push("permissions");
+        }
+        if (self.uid, self.gid) != (earlier.uid, earlier.gid) {
+            changes.push("owner");
+        }
+        changes
+    }
+
+    /// True when a write would have shown up here: same size and the same
+    /// known modification time.
+    pub fn looks_unwritten_since(&self, earlier: &FileMetadata) -> bool {
+        self.size == earlier.size && self.modified.is_some() && self.modified == earlier.modified
+    }
+
+    /// Permissions as `ls -l` shows them, e.g. `rw-r--r--`, or
+    /// "read-only"/"writable" where there are no mode bits.
+    pub fn permissions(&self) -> String {
+        match self.mode {
+            Some(mode) => (0..9)
+                .map(|i| if mode & (0o400 >> i) != 0 { ['r', 'w', 'x'][i % 3] } else { '-' })
+                .collect(),
+            None if self.readonly => "read-only".to_string(),
+            None => "writable".to_string(),
+        }
+    }
+}
+
+impl From<&std::fs::Metadata> for FileMetadata {
+    fn from(m: &std::fs::Metadata) -> Self {
+        #[cfg(unix)]
+        let (inode, device, mode, uid, gid) = {
+            use std::os::unix::fs::MetadataExt;
+            (Some(m.ino()), Some(m.dev()), Some(m.mode() & 0o7777), Some(m.uid()), Some(m.gid()))
+        };
+        #[cfg(not(unix))]
+        let (inode, device, mode, uid, gid) = (None, None, None, None, None);
+        FileMetadata {
+            size: m.len(),
+            modified: m.modified().ok().map(chrono::DateTime::<chrono::Utc>::from),
+            inode,
+            device,
+            mode,
+            readonly: m.permissions().readonly(),
+            uid,
+            gid,
+        }
+    }
+}
+
+/// Per-entry counts for a checksum manifest verified as a whole.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestSummary {
+    pub total: usize,
+    pub ok: usize,
+    pub failed: usize,
+    pub missing: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,7 +312,43 @@ pub struct VerificationRecord {
     pub algorithm: Algorithm,
     pub computed_hash: String,
     pub reference_hash: Option<String>,
+    /// Encoding the reference hash was given in, once it has been matched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_encoding: Option<HashEncoding>,
+    /// Every digest computed in the same pass, including `algorithm` itself.
+    /// Empty when only a single algorithm was requested.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub digests: BTreeMap<Algorithm, String>,
+    /// Set when `algorithm` is a non-cryptographic checksum, so a match is
+    /// not mistaken for a tamper-resistance guarantee.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub integrity_only: bool,
+    /// Set when this record covers a whole checksum manifest; `file_path`
+    /// is then the manifest and `computed_hash` its own digest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<ManifestSummary>,
+    /// Size, times and ownership of the file as it was hashed; `None` for
+    /// streams and records from before metadata was captured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<FileMetadata>,
+    /// For a re-verification, the id of the record it re-checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverify: Option<ReverifyOutcome>,
+    /// Hash of the record stored before this one, linking the history into a
+    /// tamper-evident chain. Filled in by `storage` when the record is saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<String>,
     pub status: VerificationStatus,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
+
+/// User preferences that persist between runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Settings {
+    /// Keep a `Cancelled` entry in history when a verification is stopped.
+    #[serde(default)]
+    pub record_cancelled: bool,
+}
@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+/// Minimum time between progress updates passed on to the user.
+pub const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Throughput and time remaining for a running hash, fed with the running
+/// byte count reported by `hashers::compute_hashes_with_progress`.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    started: Instant,
+    pub bytes: u64,
+    /// Expected size in bytes; unknown for stdin and other streams.
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    pub fn new(total: Option<u64>) -> Self {
+        Progress { started: Instant::now(), bytes: 0, total }
+    }
+
+    pub fn update(&mut self, bytes: u64) {
+        self.bytes = bytes;
+    }
+
+    /// Share of `total` processed so far, in `0.0..=1.0`.
+    pub fn fraction(&self) -> Option<f32> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.bytes as f64 / total as f64).min(1.0) as f32),
+            None => None,
+        }
+    }
+
+    pub fn bytes_per_sec(&self) -> f64 {
+        let secs = self.started.elapsed().as_secs_f64();
+        if secs > 0.0 { self.bytes as f64 / secs } else { 0.0 }
+    }
+
+    /// Estimated time left at the average rate so far.
+    pub fn eta(&self) -> Option<Duration> {
+        let total = self.total?;
+        let rate = self.bytes_per_sec();
+        if rate <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(total.saturating_sub(self.bytes) as f64 / rate))
+    }
+
+    /// e.g. `1.2 GB of 40.0 GB · 350.2 MB/s · 1m 52s left`
+    pub fn summary(&self) -> String {
+        let mut s = format_bytes(self.bytes);
+        if let Some(total) = self.total {
+            s.push_str(&format!(" of {}", format_bytes(total)));
+        }
+        s.push_str(&format!(" · {}/s", format_bytes(self.bytes_per_sec() as u64)));
+        if let Some(eta) = self.eta() {
+            s.push_str(&format!(" · {} left", format_duration(eta)));
+        }
+        s
+    }
+}
+
+/// Decimal units, matching how drive and download sizes are usually quoted.
+pub fn format_bytes(n: u64) -> String {
+    const UNITS: [&str; 5] = ["kB", "MB", "GB", "TB", "PB"];
+    if n < 1000 {
+        return format!("{} B", n);
+    }
+    let mut value = n as f64;
+    let mut unit = "B";
+    for u in UNITS {
+        if value < 1000.0 { break; }
+        value /= 1000.0;
+        unit = u;
+    }
+    format!("{:.1} {}", value, unit)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
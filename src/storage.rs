@@ -1,20 +1,690 @@
-use crate::models::VerificationRecord;
-use serde_json;
-use std::path::PathBuf;
+use crate::audit::{self, ChainIssue, ChainReport};
+use crate::models::{Settings, VerificationRecord};
+use anyhow::{Context, Result};
+use ed25519_dalek::SigningKey;
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension, Transaction, TransactionBehavior};
 use std::fs;
-use anyhow::Result;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-const DB_FILE: &str = "verifications.json";
+const DB_FILE: &str = "history.sqlite3";
+/// Last known-good copy of the database, refreshed at most once per `BACKUP_INTERVAL`.
+const BACKUP_FILE: &str = "history.sqlite3.bak";
+const BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How long to wait for another process (a second window, a CLI run) to
+/// finish writing before giving up.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const SETTINGS_FILE: &str = "settings.json";
+/// Ed25519 key that signs the history chain head, if the user created one.
+const KEY_FILE: &str = "history.key";
+/// History file written by earlier versions, relative to the launch directory.
+const LEGACY_JSON_FILE: &str = "verifications.json";
 
-pub fn load_all() -> Vec<VerificationRecord> {
-    let path = PathBuf::from(DB_FILE);
-    if !path.exists() { return Vec::new(); }
-    let s = fs::read_to_string(path).unwrap_or_default();
-    serde_json::from_str(&s).unwrap_or_default()
+/// Upgrade steps for the JSON of a stored record: `RECORD_MIGRATIONS[i]`
+/// turns a version `i + 1` record into version `i + 2`. Records written
+/// before versioning (including `verifications.json`) are version 1.
+const RECORD_MIGRATIONS: &[fn(&mut serde_json::Value)] = &[mark_unverified];
+/// Version of the records this build writes.
+pub const RECORD_VERSION: u32 = RECORD_MIGRATIONS.len() as u32 + 1;
+
+/// Upgrade steps for the database layout, applied in order; the number
+/// applied so far is kept in `PRAGMA user_version`.
+const DB_MIGRATIONS: &[&str] = &[
+    "ALTER TABLE records ADD COLUMN record_version INTEGER NOT NULL DEFAULT 1;
+     CREATE TABLE quarantine (
+         id             TEXT,
+         record_version INTEGER NOT NULL,
+         data           TEXT NOT NULL,
+         error          TEXT NOT NULL,
+         source         TEXT NOT NULL,
+         quarantined_at INTEGER NOT NULL
+     );",
+    // Audit chain; existing records are linked by `link_existing`.
+    "ALTER TABLE records ADD COLUMN seq INTEGER;
+     ALTER TABLE records ADD COLUMN entry_hash TEXT;
+     CREATE UNIQUE INDEX records_seq ON records(seq);
+     ALTER TABLE quarantine ADD COLUMN seq INTEGER;
+     ALTER TABLE quarantine ADD COLUMN entry_hash TEXT;
+     CREATE TABLE chain_head (
+         id        INTEGER PRIMARY KEY CHECK (id = 0),
+         seq       INTEGER NOT NULL,
+         hash      TEXT NOT NULL,
+         signature TEXT
+     );",
+];
+/// Number of `DB_MIGRATIONS` after which records carry `seq` and `entry_hash`.
+const DB_CHAINED: usize = 2;
+
+/// Where history and settings live: `$VERIFILE_DATA_DIR` if set, otherwise
+/// the platform data directory (`$XDG_DATA_HOME/verifile` on Linux).
+pub fn data_dir() -> PathBuf {
+    match std::env::var_os("VERIFILE_DATA_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("verifile"),
+    }
+}
+
+/// Where the history signing key is kept.
+pub fn key_path() -> PathBuf {
+    data_dir().join(KEY_FILE)
+}
+
+/// The verification history database.
+///
+/// Each record is stored whole as JSON, with the fields we search and sort
+/// on copied into indexed columns. Records are append-only and linked into
+/// a hash chain (see `audit`), so edits can be detected by `verify_chain`.
+pub struct History {
+    conn: Connection,
+    path: PathBuf,
+    key: Option<SigningKey>,
+    warnings: Vec<String>,
+}
+
+impl History {
+    /// Open the history in `data_dir()`; see `open_in`.
+    pub fn open_default() -> Result<History> {
+        History::open_in(&data_dir())
+    }
+
+    /// Open the history kept in the data directory `dir`, importing a
+    /// `verifications.json` from the working directory the first time it is
+    /// seen. The chain head is signed with the key in `dir` if there is one.
+    ///
+    /// A damaged database is moved aside and replaced by the latest backup
+    /// (or a fresh database); `warnings()` then says what happened.
+    pub fn open_in(dir: &Path) -> Result<History> {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let path = dir.join(DB_FILE);
+        let backup = dir.join(BACKUP_FILE);
+        let mut history = match History::open_checked(&path) {
+            Ok(history) => history,
+            Err(e) if is_corrupt(&e) => {
+                let note = restore_backup(&path, &backup, &e)?;
+                let mut history = History::open(&path)?;
+                history.warnings.push(note);
+                history
+            }
+            Err(e) => return Err(e),
+        };
+        match audit::load_key(&dir.join(KEY_FILE)) {
+            Ok(key) => history.key = key,
+            Err(e) => history.warnings.push(format!("History will not be signed: {:#}", e)),
+        }
+        if Path::new(LEGACY_JSON_FILE).is_file() {
+            if let Err(e) = history.import_json_once(Path::new(LEGACY_JSON_FILE)) {
+                history.warnings.push(format!("Could not import {} (left unchanged): {:#}", LEGACY_JSON_FILE, e));
+            }
+        }
+        if let Err(e) = history.refresh_backup(&backup) {
+            history.warnings.push(format!("Could not back up history: {:#}", e));
+        }
+        Ok(history)
+    }
+
+    /// Open (creating if needed) the database at `path`.
+    pub fn open(path: &Path) -> Result<History> {
+        let conn = Connection::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        // WAL keeps the last committed state intact if we die mid-write and
+        // lets readers carry on while another process writes.
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS records (
+                 id            TEXT PRIMARY KEY,
+                 timestamp     INTEGER NOT NULL,
+                 file_path     TEXT NOT NULL,
+                 computed_hash TEXT NOT NULL,
+                 data          TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS records_timestamp ON records(timestamp);
+             CREATE INDEX IF NOT EXISTS records_file_path ON records(file_path);
+             CREATE INDEX IF NOT EXISTS records_computed_hash ON records(computed_hash);
+             CREATE TABLE IF NOT EXISTS imported_files (
+                 path        TEXT PRIMARY KEY,
+                 imported_at INTEGER NOT NULL
+             );",
+        )?;
+        migrate_db(&conn).with_context(|| format!("failed to upgrade {}", path.display()))?;
+        Ok(History { conn, path: path.to_path_buf(), key: None, warnings: Vec::new() })
+    }
+
+    /// Like `open`, but also runs SQLite's quick integrity check.
+    pub fn open_checked(path: &Path) -> Result<History> {
+        let history = History::open(path)?;
+        let result: String = history.conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+        if result != "ok" {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CORRUPT),
+                Some(result),
+            )
+            .into());
+        }
+        Ok(history)
+    }
+
+    /// Problems met while opening that did not stop the history from
+    /// working, e.g. a restored backup or a failed import.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Sign the chain head with `key` from now on.
+    pub fn set_signing_key(&mut self, key: Option<SigningKey>) {
+        self.key = key;
+    }
+
+    /// Append one record to the end of the chain. Records are never
+    /// replaced, so adding an id that is already stored is an error.
+    pub fn insert(&self, rec: &VerificationRecord) -> Result<()> {
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        if contains(&tx, &rec.id)? {
+            anyhow::bail!("record {} is already in the history", rec.id);
+        }
+        append_record(&tx, rec, self.key.as_ref())?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Sign the current chain head with the signing key, e.g. right after
+    /// the key was created.
+    pub fn sign_head(&self) -> Result<()> {
+        let key = self.key.as_ref().context("no history signing key")?;
+        if let Some((seq, hash, _)) = chain_head(&self.conn)? {
+            self.conn.execute(
+                "UPDATE chain_head SET signature = ?1 WHERE id = 0",
+                [audit::sign_head(key, seq, &hash)],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Walk the whole chain, including quarantined records, and report any
+    /// record that was removed, reordered or modified after it was stored.
+    /// The head signature is checked when a signing key is set.
+    pub fn verify_chain(&self) -> Result<ChainReport> {
+        let mut stmt = self.conn.prepare(
+            "SELECT seq, id, data, entry_hash FROM records WHERE seq IS NOT NULL
+             UNION ALL
+             SELECT seq, id, data, entry_hash FROM quarantine WHERE seq IS NOT NULL
+             ORDER BY 1",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)? as u64,
+                row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+
+        let mut issues = Vec::new();
+        let mut records = 0;
+        let mut last_seq = 0;
+        let mut prev: Option<String> = None;
+        for row in rows {
+            let (seq, id, data, stored_hash) = row?;
+            records += 1;
+            let gap = seq != last_seq + 1;
+            if gap {
+                issues.push(if seq > last_seq + 1 {
+                    ChainIssue::Missing { after: last_seq, count: seq - last_seq - 1 }
+                } else {
+                    ChainIssue::BrokenLink { seq, id: id.clone() }
+                });
+            }
+            let hash = audit::entry_hash(&data);
+            if stored_hash.as_deref() != Some(hash.as_str()) {
+                issues.push(ChainIssue::Modified { seq, id: id.clone() });
+            }
+            let linked = serde_json::from_str::<serde_json::Value>(&data)
+                .ok()
+                .and_then(|v| v.get("prev_hash").and_then(|h| h.as_str()).map(String::from));
+            if !gap && linked != prev {
+                issues.push(ChainIssue::BrokenLink { seq, id });
+            }
+            // Follow the stored hash so one edited record is reported once,
+            // not again as a broken link on the next one.
+            prev = Some(stored_hash.unwrap_or(hash));
+            last_seq = seq;
+        }
+
+        let mut stmt = self.conn.prepare("SELECT id FROM records WHERE seq IS NULL")?;
+        for id in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            issues.push(ChainIssue::Unchained { id: id? });
+        }
+
+        let head = chain_head(&self.conn)?;
+        let mut signed = false;
+        match &head {
+            None if records > 0 => issues.push(ChainIssue::HeadMismatch),
+            None => {}
+            Some((seq, hash, signature)) => {
+                if *seq != last_seq || Some(hash) != prev.as_ref() {
+                    issues.push(ChainIssue::HeadMismatch);
+                }
+                if let Some(key) = &self.key {
+                    match signature {
+                        None => issues.push(ChainIssue::Unsigned),
+                        Some(sig) if audit::verify_head(&key.verifying_key(), *seq, hash, sig) => signed = true,
+                        Some(_) => issues.push(ChainIssue::BadSignature),
+                    }
+                }
+            }
+        }
+        Ok(ChainReport { records, head: head.map(|(_, hash, _)| hash), signed, issues })
+    }
+
+    /// Every record, newest first. See `load` for what happens to records
+    /// that cannot be read.
+    pub fn load_all(&self) -> Result<Vec<VerificationRecord>> {
+        Ok(self.load()?.records)
+    }
+
+    /// Every record, newest first, upgraded to `RECORD_VERSION` as it is read.
+    /// The stored JSON is left as it was, since the audit chain covers it.
+    ///
+    /// A record that cannot be read is moved to the `quarantine` table rather
+    /// than failing the whole load; one written by a newer VeriFile is left
+    /// alone and skipped. Either way the returned warnings say so.
+    pub fn load(&self) -> Result<Loaded> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, record_version, data FROM records ORDER BY timestamp DESC, rowid DESC")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?, row.get::<_, String>(2)?)))?;
+        let mut records = Vec::new();
+        let mut bad = Vec::new();
+        let mut newer = 0;
+        for row in rows {
+            let (id, version, data) = row?;
+            if version > RECORD_VERSION {
+                newer += 1;
+                continue;
+            }
+            match decode_record(&data, version) {
+                Ok(rec) => records.push(rec),
+                Err(e) => bad.push((id, data, format!("{:#}", e))),
+            }
+        }
+
+        if !bad.is_empty() {
+            // Quarantined records keep their place in the chain.
+            let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+            for (id, data, error) in &bad {
+                tx.execute(
+                    "INSERT INTO quarantine (id, record_version, data, error, source, quarantined_at, seq, entry_hash)
+                     SELECT id, record_version, data, ?3, 'history', ?4, seq, entry_hash
+                     FROM records WHERE id = ?1 AND data = ?2",
+                    params![id, data, error, chrono::Utc::now().timestamp()],
+                )?;
+                tx.execute("DELETE FROM records WHERE id = ?1 AND data = ?2", params![id, data])?;
+            }
+            tx.commit()?;
+        }
+
+        let mut warnings = Vec::new();
+        if !bad.is_empty() {
+            warnings.push(format!(
+                "{} history record{} could not be read and {} moved to the quarantine table in {}.",
+                bad.len(),
+                if bad.len() == 1 { "" } else { "s" },
+                if bad.len() == 1 { "was" } else { "were" },
+                self.path.display()
+            ));
+        }
+        if newer > 0 {
+            warnings.push(format!(
+                "{} history record{} written by a newer version of VeriFile {} not shown.",
+                newer,
+                if newer == 1 { "" } else { "s" },
+                if newer == 1 { "is" } else { "are" }
+            ));
+        }
+        Ok(Loaded { records, warnings })
+    }
+
+    /// Records whose id starts with `prefix`. Unreadable records are skipped
+    /// here; `load` quarantines them.
+    pub fn find_by_id_prefix(&self, prefix: &str) -> Result<Vec<VerificationRecord>> {
+        let escaped = prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        let mut stmt = self
+            .conn
+            .prepare("SELECT record_version, data FROM records WHERE id LIKE ?1 ESCAPE '\\' ORDER BY timestamp DESC")?;
+        let rows = stmt.query_map([format!("{}%", escaped)], |row| Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?)))?;
+        let mut out = Vec::new();
+        for row in rows {
+            let (version, data) = row?;
+            if version <= RECORD_VERSION {
+                if let Ok(rec) = decode_record(&data, version) {
+                    out.push(rec);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// How many records are in the quarantine table.
+    pub fn quarantined(&self) -> Result<usize> {
+        let count: i64 = self.conn.query_row("SELECT COUNT(*) FROM quarantine", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Copy the records of a JSON history file (the pre-database format) into
+    /// the database, unless this file was imported before. Entries that cannot
+    /// be read are quarantined. Returns how many records were imported.
+    pub fn import_json_once(&self, path: &Path) -> Result<usize> {
+        let key = fs::canonicalize(path)?.to_string_lossy().into_owned();
+        // Take the write lock before checking, so two processes starting at
+        // once do not both import.
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let seen: Option<i64> = tx
+            .query_row("SELECT imported_at FROM imported_files WHERE path = ?1", [&key], |row| row.get(0))
+            .optional()?;
+        if seen.is_some() {
+            return Ok(0);
+        }
+        let text = fs::read_to_string(path)?;
+        let entries: Vec<serde_json::Value> =
+            serde_json::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))?;
+
+        // Oldest first, so the chain follows the original order. Ids already
+        // stored (e.g. from another copy of the file) are skipped.
+        let mut imported = 0;
+        for entry in entries.into_iter().rev() {
+            match upgrade_record(entry.clone(), 1) {
+                Ok(rec) if contains(&tx, &rec.id)? => {}
+                Ok(rec) => {
+                    append_record(&tx, &rec, self.key.as_ref())?;
+                    imported += 1;
+                }
+                Err(e) => {
+                    let id = entry.get("id").and_then(|v| v.as_str());
+                    quarantine(&tx, id, 1, &entry.to_string(), &format!("{:#}", e), &key)?;
+                }
+            }
+        }
+        tx.execute(
+            "INSERT INTO imported_files (path, imported_at) VALUES (?1, ?2)",
+            params![key, chrono::Utc::now().timestamp()],
+        )?;
+        tx.commit()?;
+        Ok(imported)
+    }
+
+    /// Append `records` read from the log at `source`, unless this exact log
+    /// (same path and contents) was imported before, in which case `None`
+    /// is returned. Records are added in the order given.
+    pub fn import_log_once(&self, source: &Path, records: &[VerificationRecord]) -> Result<Option<usize>> {
+        let contents = fs::read(source).with_context(|| format!("failed to read {}", source.display()))?;
+        let key = format!(
+            "{}#{}",
+            fs::canonicalize(source)?.to_string_lossy(),
+            audit::entry_hash(&String::from_utf8_lossy(&contents))
+        );
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let seen: Option<i64> = tx
+            .query_row("SELECT imported_at FROM imported_files WHERE path = ?1", [&key], |row| row.get(0))
+            .optional()?;
+        if seen.is_some() {
+            return Ok(None);
+        }
+        for rec in records {
+            append_record(&tx, rec, self.key.as_ref())?;
+        }
+        tx.execute(
+            "INSERT INTO imported_files (path, imported_at) VALUES (?1, ?2)",
+            params![key, chrono::Utc::now().timestamp()],
+        )?;
+        tx.commit()?;
+        Ok(Some(records.len()))
+    }
+
+    /// Write a consistent copy of the database to `backup` if the existing
+    /// one is missing or older than `BACKUP_INTERVAL`. The copy is written
+    /// next to it and renamed into place, so a crash never leaves a torn
+    /// backup behind.
+    pub fn refresh_backup(&self, backup: &Path) -> Result<()> {
+        let fresh = fs::metadata(backup)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age < BACKUP_INTERVAL);
+        if fresh {
+            return Ok(());
+        }
+        // Per-process name: two instances may both decide the backup is due.
+        let tmp = sibling(backup, &format!(".{}.tmp", std::process::id()));
+        let _ = fs::remove_file(&tmp);
+        self.conn.execute("VACUUM INTO ?1", [tmp.to_string_lossy()])?;
+        fs::rename(&tmp, backup).with_context(|| format!("failed to write {}", backup.display()))?;
+        Ok(())
+    }
+}
+
+/// `path` with `suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Whether `err` means the database file itself is damaged, as opposed to
+/// e.g. being locked or unreadable.
+fn is_corrupt(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| {
+        e.downcast_ref::<rusqlite::Error>()
+            .and_then(|e| e.sqlite_error_code())
+            .is_some_and(|code| matches!(code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase))
+    })
 }
 
-pub fn save_all(records: &[VerificationRecord]) -> Result<()> {
-    let s = serde_json::to_string_pretty(records)?;
-    fs::write(DB_FILE, s)?;
+/// Move the damaged database at `path` (and its WAL files) aside and put the
+/// backup in its place, if there is one. Returns a note for the user.
+fn restore_backup(path: &Path, backup: &Path, err: &anyhow::Error) -> Result<String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let aside = sibling(path, &format!(".damaged-{}", stamp));
+    for suffix in ["", "-wal", "-shm"] {
+        let from = sibling(path, suffix);
+        if from.exists() {
+            fs::rename(&from, sibling(&aside, suffix))
+                .with_context(|| format!("failed to move aside {}", from.display()))?;
+        }
+    }
+    let restored = backup.is_file() && History::open_checked(backup).is_ok();
+    if restored {
+        fs::copy(backup, path).with_context(|| format!("failed to restore {}", backup.display()))?;
+    }
+    Ok(format!(
+        "History database was damaged ({:#}) and was moved to {}. {}",
+        err,
+        aside.display(),
+        if restored { "Restored the most recent backup; later records may be missing." } else { "No usable backup was found, so history starts empty." }
+    ))
+}
+
+/// Replace `path` with `contents` without ever leaving a half-written file:
+/// write a temporary file alongside, flush it to disk, then rename it over.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp = sibling(path, ".tmp");
+    let mut file = fs::File::create(&tmp).with_context(|| format!("failed to create {}", tmp.display()))?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp, path).with_context(|| format!("failed to replace {}", path.display()))?;
     Ok(())
 }
+
+/// Bring the database layout up to date, refusing one from a newer build.
+fn migrate_db(conn: &Connection) -> Result<()> {
+    let applied: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if applied as usize == DB_MIGRATIONS.len() {
+        return Ok(());
+    }
+    // Re-read under the write lock in case another process just did this.
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    let applied = tx.pragma_query_value(None, "user_version", |row| row.get::<_, u32>(0))? as usize;
+    if applied > DB_MIGRATIONS.len() {
+        anyhow::bail!("the history database was written by a newer version of VeriFile");
+    }
+    for step in &DB_MIGRATIONS[applied..] {
+        tx.execute_batch(step)?;
+    }
+    if applied < DB_CHAINED {
+        link_existing(&tx)?;
+    }
+    tx.pragma_update(None, "user_version", DB_MIGRATIONS.len() as u32)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Parse a stored record written at `version`, upgrading it as needed.
+fn decode_record(data: &str, version: u32) -> Result<VerificationRecord> {
+    upgrade_record(serde_json::from_str(data)?, version)
+}
+
+/// Version 1 to 2: a file hashed without a reference hash was recorded as
+/// `Success`; it is `Unverified`. Manifest records and imported check logs
+/// with no digest were verified by other means and keep their status.
+fn mark_unverified(record: &mut serde_json::Value) {
+    let hashed = record["computed_hash"].as_str().is_some_and(|h| !h.is_empty());
+    if record["status"] == "Success" && record["reference_hash"].is_null() && record["manifest"].is_null() && hashed {
+        record["status"] = "Unverified".into();
+    }
+}
+
+fn upgrade_record(mut value: serde_json::Value, version: u32) -> Result<VerificationRecord> {
+    for step in &RECORD_MIGRATIONS[version.max(1) as usize - 1..] {
+        step(&mut value);
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Link records stored before the audit chain existed, oldest first. Runs
+/// once, as part of the migration that adds the chain.
+fn link_existing(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT id, data FROM records ORDER BY timestamp, rowid")?;
+    let rows: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    let mut prev: Option<String> = None;
+    let mut seq = 0;
+    for (id, data) in rows {
+        let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_str(&data) else {
+            // Not a record at all; `load` would quarantine it anyway.
+            quarantine(conn, Some(&id), 1, &data, "not a JSON object", "history")?;
+            conn.execute("DELETE FROM records WHERE id = ?1", [&id])?;
+            continue;
+        };
+        if let Some(p) = &prev {
+            fields.insert("prev_hash".into(), p.clone().into());
+        }
+        let data = serde_json::Value::Object(fields).to_string();
+        let hash = audit::entry_hash(&data);
+        seq += 1;
+        conn.execute(
+            "UPDATE records SET data = ?2, seq = ?3, entry_hash = ?4 WHERE id = ?1",
+            params![id, data, seq as i64, hash],
+        )?;
+        prev = Some(hash);
+    }
+    if let Some(hash) = prev {
+        set_head(conn, seq, &hash, None)?;
+    }
+    Ok(())
+}
+
+fn contains(conn: &Connection, id: &str) -> Result<bool> {
+    Ok(conn.query_row("SELECT EXISTS(SELECT 1 FROM records WHERE id = ?1)", [id], |row| row.get(0))?)
+}
+
+/// Position, hash and signature of the newest record in the chain.
+fn chain_head(conn: &Connection) -> Result<Option<(u64, String, Option<String>)>> {
+    Ok(conn
+        .query_row("SELECT seq, hash, signature FROM chain_head WHERE id = 0", [], |row| {
+            Ok((row.get::<_, i64>(0)? as u64, row.get(1)?, row.get(2)?))
+        })
+        .optional()?)
+}
+
+fn set_head(conn: &Connection, seq: u64, hash: &str, key: Option<&SigningKey>) -> Result<()> {
+    let signature = key.map(|k| audit::sign_head(k, seq, hash));
+    conn.execute(
+        "INSERT OR REPLACE INTO chain_head (id, seq, hash, signature) VALUES (0, ?1, ?2, ?3)",
+        params![seq as i64, hash, signature],
+    )?;
+    Ok(())
+}
+
+/// Store `rec` after the current chain head, pointing back at it. Callers
+/// hold a write transaction so the head cannot move underneath.
+fn append_record(conn: &Connection, rec: &VerificationRecord, key: Option<&SigningKey>) -> Result<()> {
+    let (seq, prev_hash) = match chain_head(conn)? {
+        Some((seq, hash, _)) => (seq + 1, Some(hash)),
+        None => (1, None),
+    };
+    let rec = VerificationRecord { prev_hash, ..rec.clone() };
+    let data = serde_json::to_string(&rec)?;
+    let hash = audit::entry_hash(&data);
+    conn.execute(
+        "INSERT INTO records (id, timestamp, file_path, computed_hash, data, record_version, seq, entry_hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            rec.id,
+            rec.timestamp.timestamp(),
+            rec.file_path.to_string_lossy(),
+            rec.computed_hash,
+            data,
+            RECORD_VERSION,
+            seq as i64,
+            hash,
+        ],
+    )?;
+    set_head(conn, seq, &hash, key)
+}
+
+/// Keep an unreadable record, with why it could not be read and where it
+/// came from, so nothing is lost and it can be repaired by hand.
+fn quarantine(conn: &Connection, id: Option<&str>, version: u32, data: &str, error: &str, source: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO quarantine (id, record_version, data, error, source, quarantined_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![id, version, data, error, source, chrono::Utc::now().timestamp()],
+    )?;
+    Ok(())
+}
+
+/// The verification history as read by `History::load` or `load_all`.
+pub struct Loaded {
+    /// Newest first.
+    pub records: Vec<VerificationRecord>,
+    /// Anything that was restored, skipped or quarantined on the way.
+    pub warnings: Vec<String>,
+}
+
+/// Open the default history and load it, newest first, with the warnings
+/// from both opening and reading it. The open history is returned so that
+/// later records can be appended without opening it again.
+pub fn load_all() -> Result<(History, Loaded)> {
+    let mut history = History::open_default()?;
+    let mut loaded = history.load()?;
+    let mut warnings = std::mem::take(&mut history.warnings);
+    warnings.append(&mut loaded.warnings);
+    loaded.warnings = warnings;
+    Ok((history, loaded))
+}
+
+/// Load saved settings, falling back to defaults if none are stored.
+pub fn load_settings() -> Settings {
+    fs::read_to_string(data_dir().join(SETTINGS_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(settings: &Settings) -> Result<()> {
+    let dir = data_dir();
+    fs::create_dir_all(&dir)?;
+    write_atomic(&dir.join(SETTINGS_FILE), serde_json::to_string_pretty(settings)?.as_bytes())
+}
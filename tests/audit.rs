@@ -0,0 +1,134 @@
+mod common;
+
+use common::{sample, scratch_dir};
+use ed25519_dalek::SigningKey;
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use verifile::audit::ChainIssue;
+use verifile::storage::History;
+
+/// A history of five records and a raw connection for tampering with it.
+fn chained(name: &str) -> (PathBuf, History, Connection) {
+    let dir = scratch_dir(name);
+    let db = dir.join("history.sqlite3");
+    let history = History::open(&db).unwrap();
+    for i in 0..5 {
+        history.insert(&sample(i)).unwrap();
+    }
+    (dir, history, Connection::open(&db).unwrap())
+}
+
+#[test]
+fn records_link_to_the_one_before() {
+    let (dir, history, _raw) = chained("audit-links");
+    let mut all = history.load_all().unwrap();
+    all.reverse();
+    assert!(all[0].prev_hash.is_none());
+    assert!(all[1..].iter().all(|r| r.prev_hash.is_some()));
+
+    let report = history.verify_chain().unwrap();
+    assert!(report.is_intact(), "{:?}", report.issues);
+    assert_eq!(report.records, 5);
+    assert!(history.insert(&all[2]).is_err(), "records are never replaced");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn detects_modified_deleted_and_reordered_records() {
+    let (dir, history, raw) = chained("audit-modified");
+    raw.execute("UPDATE records SET data = replace(data, 'sample.bin', 'other.bin') WHERE seq = 2", []).unwrap();
+    let issues = history.verify_chain().unwrap().issues;
+    assert!(matches!(issues.as_slice(), [ChainIssue::Modified { seq: 2, .. }]), "{:?}", issues);
+    drop((history, raw));
+    fs::remove_dir_all(dir).unwrap();
+
+    let (dir, history, raw) = chained("audit-deleted");
+    raw.execute("DELETE FROM records WHERE seq = 3", []).unwrap();
+    let issues = history.verify_chain().unwrap().issues;
+    assert_eq!(issues, vec![ChainIssue::Missing { after: 2, count: 1 }]);
+    drop((history, raw));
+    fs::remove_dir_all(dir).unwrap();
+
+    let (dir, history, raw) = chained("audit-reordered");
+    raw.execute_batch(
+        "UPDATE records SET seq = -1 WHERE seq = 2;
+         UPDATE records SET seq = 2 WHERE seq = 3;
+         UPDATE records SET seq = 3 WHERE seq = -1;",
+    )
+    .unwrap();
+    let issues = history.verify_chain().unwrap().issues;
+    assert!(issues.iter().any(|i| matches!(i, ChainIssue::BrokenLink { .. })), "{:?}", issues);
+    drop((history, raw));
+    fs::remove_dir_all(dir).unwrap();
+
+    let (dir, history, raw) = chained("audit-truncated");
+    raw.execute("DELETE FROM records WHERE seq = 5", []).unwrap();
+    assert_eq!(history.verify_chain().unwrap().issues, vec![ChainIssue::HeadMismatch]);
+    drop((history, raw));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn signed_head_rejects_a_rewritten_chain() {
+    let dir = scratch_dir("audit-signed");
+    let mut history = History::open(&dir.join("history.sqlite3")).unwrap();
+    history.insert(&sample(0)).unwrap();
+    history.set_signing_key(Some(SigningKey::from_bytes(&[7; 32])));
+    history.sign_head().unwrap();
+    history.insert(&sample(1)).unwrap();
+    let report = history.verify_chain().unwrap();
+    assert!(report.is_intact() && report.signed, "{:?}", report.issues);
+
+    history.set_signing_key(Some(SigningKey::from_bytes(&[8; 32])));
+    assert_eq!(history.verify_chain().unwrap().issues, vec![ChainIssue::BadSignature]);
+    drop(history);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn quarantined_records_stay_in_the_chain() {
+    let (dir, history, raw) = chained("audit-quarantine");
+    // Damage one record so it can no longer be read.
+    raw.execute("UPDATE records SET data = replace(data, '\"status\"', '\"statu\"') WHERE seq = 4", []).unwrap();
+    assert_eq!(history.load().unwrap().records.len(), 4);
+    assert_eq!(history.quarantined().unwrap(), 1);
+    // Reported as the modification it is, not as a missing record.
+    let issues = history.verify_chain().unwrap().issues;
+    assert!(matches!(issues.as_slice(), [ChainIssue::Modified { seq: 4, .. }]), "{:?}", issues);
+    drop((history, raw));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn links_records_from_before_the_chain() {
+    let dir = scratch_dir("audit-upgrade");
+    let db = dir.join("history.sqlite3");
+    // The layout the first database version created.
+    let raw = Connection::open(&db).unwrap();
+    raw.execute_batch(
+        "CREATE TABLE records (
+             id TEXT PRIMARY KEY, timestamp INTEGER NOT NULL, file_path TEXT NOT NULL,
+             computed_hash TEXT NOT NULL, data TEXT NOT NULL
+         );",
+    )
+    .unwrap();
+    for i in 0..3 {
+        let rec = sample(i);
+        raw.execute(
+            "INSERT INTO records VALUES (?1, ?2, 'sample.bin', ?3, ?4)",
+            rusqlite::params![rec.id, rec.timestamp.timestamp(), rec.computed_hash, serde_json::to_string(&rec).unwrap()],
+        )
+        .unwrap();
+    }
+    drop(raw);
+
+    let history = History::open(&db).unwrap();
+    let report = history.verify_chain().unwrap();
+    assert!(report.is_intact(), "{:?}", report.issues);
+    assert_eq!(report.records, 3);
+    history.insert(&sample(3)).unwrap();
+    assert!(history.verify_chain().unwrap().is_intact());
+    drop(history);
+    fs::remove_dir_all(dir).unwrap();
+}
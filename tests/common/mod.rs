@@ -0,0 +1,51 @@
+//! Fixtures shared by the integration tests. Each test crate uses only some
+//! of them.
+#![allow(dead_code)]
+
+use chrono::{Duration, TimeZone, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use verifile::{file_ops, Algorithm, VerificationRecord, VerificationStatus};
+
+/// A fresh directory under the system temp dir, unique to this test process.
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("verifile-test-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The SHA-256 record of the one-byte file `[i]`, `i` seconds after now.
+pub fn sample(i: u8) -> VerificationRecord {
+    let mut rec = file_ops::verify_reader(&[i][..], Path::new("sample.bin"), &Algorithm::Sha256, &[], None).unwrap();
+    rec.timestamp += Duration::seconds(i as i64);
+    rec
+}
+
+/// A synthetic record: SHA-256 for even `i`, BLAKE3 for odd, failed for
+/// every fifth, one every six hours from 2024-01-01 00:00 UTC.
+pub fn record(i: usize) -> VerificationRecord {
+    VerificationRecord {
+        id: i.to_string(),
+        file_name: format!("file-{:05}.bin", i),
+        file_path: PathBuf::from(format!("/data/set-{}/file-{:05}.bin", i % 3, i)),
+        algorithm: if i.is_multiple_of(2) { Algorithm::Sha256 } else { Algorithm::Blake3 },
+        computed_hash: format!("{:064x}", i),
+        reference_hash: None,
+        reference_encoding: None,
+        digests: Default::default(),
+        integrity_only: false,
+        manifest: None,
+        metadata: None,
+        parent_id: None,
+        reverify: None,
+        prev_hash: None,
+        status: if i.is_multiple_of(5) { VerificationStatus::Failed } else { VerificationStatus::Success },
+        timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::hours(6 * i as i64),
+    }
+}
+
+/// `record(0)` to `record(n - 1)`.
+pub fn records(n: usize) -> Vec<VerificationRecord> {
+    (0..n).map(record).collect()
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::records;
+use verifile::export::{export, ExportFormat};
+use verifile::history::HistoryQuery;
+use verifile::{Algorithm, VerificationRecord, VerificationStatus};
+
+#[test]
+fn csv_has_header_and_quotes_awkward_fields() {
+    let mut recs = records(3);
+    recs[1].file_name = "a, \"quoted\" name".to_string();
+    let csv = export(&recs, &HistoryQuery::default(), ExportFormat::Csv).unwrap();
+    let lines: Vec<&str> = csv.split("\r\n").filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("id,timestamp,file_name,"));
+    // Newest first, as in the history view.
+    assert!(lines[1].starts_with("2,"));
+    assert!(lines[2].contains(",\"a, \"\"quoted\"\" name\","));
+}
+
+#[test]
+fn csv_defuses_formula_cells() {
+    let mut recs = records(6);
+    recs[4].file_name = "\t=1+1".to_string();
+    recs[5].file_name = "\r=1+1".to_string();
+    recs[0].file_name = "=HYPERLINK(\"http://x\",\"y\")".to_string();
+    recs[1].file_name = "+1".to_string();
+    recs[2].file_name = "-2+3".to_string();
+    recs[3].file_name = "@SUM(A1)".to_string();
+    let csv = export(&recs, &HistoryQuery::default(), ExportFormat::Csv).unwrap();
+    assert!(csv.contains(",\"'=HYPERLINK(\"\"http://x\"\",\"\"y\"\")\","));
+    assert!(csv.contains(",\"'\r=1+1\","));
+    for cell in [",'+1,", ",'-2+3,", ",'@SUM(A1),", ",'\t=1+1,"] {
+        assert!(csv.contains(cell), "{} in {}", cell, csv);
+    }
+}
+
+#[test]
+fn filters_by_status_algorithm_and_date() {
+    let recs = records(12);
+    let q = HistoryQuery {
+        status: Some(VerificationStatus::Failed),
+        algorithm: Some(Algorithm::Sha256),
+        from: NaiveDate::from_ymd_opt(2024, 1, 2),
+        ..HistoryQuery::default()
+    };
+    let jsonl = export(&recs, &q, ExportFormat::JsonLines).unwrap();
+    let ids: Vec<String> = jsonl
+        .lines()
+        .map(|l| serde_json::from_str::<VerificationRecord>(l).unwrap().id)
+        .collect();
+    assert_eq!(ids, ["10"]);
+}
+
+#[test]
+fn html_report_is_standalone_and_escaped() {
+    let mut recs = records(4);
+    recs[0].file_name = "<script>alert(1)</script>".to_string();
+    let html = export(&recs, &HistoryQuery::default(), ExportFormat::Html).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&lt;script&gt;"));
+    assert!(!html.contains("http://") && !html.contains("https://"));
+    // Record 0 failed.
+    assert!(html.contains("<div class=\"n pass\">3</div>Passed"));
+    assert!(html.contains("<div class=\"n fail\">1</div>Failed"));
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use common::scratch_dir;
+use std::path::Path;
+use verifile::file_ops;
+use verifile::{Algorithm, HashEncoding, VerificationStatus};
+
+#[test]
+fn verify_reader_detects_algorithm_from_reference() {
+    let reference = "sha512:ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+    let rec = file_ops::verify_reader(&b"abc"[..], Path::new("abc.txt"), &Algorithm::Sha256, &[], Some(reference)).unwrap();
+    assert_eq!(rec.status, VerificationStatus::Success);
+    assert_eq!(rec.algorithm, Algorithm::Sha512);
+    assert_eq!(rec.reference_encoding, Some(HashEncoding::Hex));
+    assert_eq!(rec.file_name, "abc.txt");
+}
+
+#[test]
+fn hashing_without_a_reference_is_unverified() {
+    let rec = file_ops::verify_reader(&b"abc"[..], Path::new("abc.txt"), &Algorithm::Sha256, &[], Some("  ")).unwrap();
+    assert_eq!(rec.status, VerificationStatus::Unverified);
+    assert!(rec.reference_hash.is_none());
+}
+
+#[test]
+fn unreadable_files_are_recorded_as_missing_or_error() {
+    let path = Path::new("/nonexistent/verifile/data.bin");
+    let err = file_ops::verify_file(path, &Algorithm::Sha256, &[], None).unwrap_err();
+    let rec = file_ops::error_record(path, &Algorithm::Sha256, None, &err);
+    assert_eq!(rec.status, VerificationStatus::Missing);
+    assert!(rec.computed_hash.is_empty());
+
+    let dir = std::env::temp_dir();
+    let err = file_ops::verify_file(&dir, &Algorithm::Sha256, &[], Some("abc")).unwrap_err();
+    let rec = file_ops::error_record(&dir, &Algorithm::Sha256, Some("abc"), &err);
+    assert!(matches!(rec.status, VerificationStatus::Error { ref kind, .. } if kind == "IsADirectory"), "{:?}", rec.status);
+    assert_eq!(rec.reference_hash.as_deref(), Some("abc"));
+}
+
+#[test]
+fn verify_reader_reports_mismatch() {
+    let rec = file_ops::verify_reader(&b"abd"[..], Path::new("abc.txt"), &Algorithm::Md5, &[], Some("900150983cd24fb0d6963f7d28e17f72")).unwrap();
+    assert_eq!(rec.status, VerificationStatus::Failed);
+}
+
+#[test]
+fn extra_algorithms_are_computed_in_the_same_pass() {
+    let rec = file_ops::verify_reader(&b"abc"[..], Path::new("abc.txt"), &Algorithm::Sha256, &[Algorithm::Crc32], None).unwrap();
+    assert_eq!(rec.digests.len(), 2);
+    assert_eq!(rec.digests[&Algorithm::Crc32], "352441c2");
+    assert!(!rec.integrity_only);
+}
+
+#[test]
+fn reverify_reports_unchanged_changed_and_missing() {
+    use verifile::models::ReverifyOutcome;
+
+    let dir = scratch_dir("reverify");
+    let path = dir.join("data.bin");
+    std::fs::write(&path, b"abc").unwrap();
+
+    let original = file_ops::verify_file(&path, &Algorithm::Sha256, &[], None).unwrap();
+    let same = file_ops::reverify(&original).unwrap();
+    assert_eq!(same.reverify, Some(ReverifyOutcome::Unchanged));
+    assert_eq!(same.status, VerificationStatus::Success);
+    assert_eq!(same.parent_id.as_deref(), Some(original.id.as_str()));
+    assert_eq!(same.reference_hash.as_deref(), Some(original.computed_hash.as_str()));
+
+    std::fs::write(&path, b"abd").unwrap();
+    let changed = file_ops::reverify(&original).unwrap();
+    assert_eq!(changed.reverify, Some(ReverifyOutcome::Changed));
+    assert_eq!(changed.status, VerificationStatus::Failed);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    let missing = file_ops::reverify(&original).unwrap();
+    assert_eq!(missing.reverify, Some(ReverifyOutcome::Missing));
+    assert_eq!(missing.status, VerificationStatus::Missing);
+    assert!(missing.computed_hash.is_empty());
+}
+
+#[test]
+fn reverify_tells_corruption_from_an_edit() {
+    use verifile::models::ReverifyOutcome;
+
+    let dir = scratch_dir("bitrot");
+    let path = dir.join("data.bin");
+    std::fs::write(&path, b"abc").unwrap();
+
+    let original = file_ops::verify_file(&path, &Algorithm::Sha256, &[], None).unwrap();
+    let meta = original.metadata.clone().expect("metadata captured");
+    assert_eq!(meta.size, 3);
+    assert!(meta.modified.is_some());
+
+    // Same length, modification time put back: what a flipped bit looks like.
+    let mtime = std::fs::metadata(&path).unwrap().modified().unwrap();
+    std::fs::write(&path, b"abd").unwrap();
+    std::fs::File::options().write(true).open(&path).unwrap().set_modified(mtime).unwrap();
+    let rotted = file_ops::reverify(&original).unwrap();
+    assert_eq!(rotted.reverify, Some(ReverifyOutcome::Corrupted));
+    assert_eq!(rotted.status, VerificationStatus::Failed);
+    assert!(rotted.metadata.as_ref().unwrap().changes_since(&meta).is_empty());
+
+    let later = mtime + std::time::Duration::from_secs(60);
+    std::fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+    let edited = file_ops::reverify(&original).unwrap();
+    assert_eq!(edited.reverify, Some(ReverifyOutcome::Changed));
+    assert_eq!(edited.metadata.as_ref().unwrap().changes_since(&meta), ["modification time"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reverify_rechecks_manifests_and_refuses_records_without_a_digest() {
+    use verifile::models::ReverifyOutcome;
+
+    let dir = scratch_dir("reverify-manifest");
+    std::fs::write(dir.join("a.txt"), b"abc").unwrap();
+    let sums = dir.join("SHA256SUMS");
+    std::fs::write(&sums, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  a.txt\n").unwrap();
+
+    let original = verifile::manifest::verify_manifest(&sums).unwrap().history_record();
+    let same = file_ops::reverify(&original).unwrap();
+    assert_eq!(same.reverify, Some(ReverifyOutcome::Unchanged));
+    assert_eq!(same.manifest.as_ref().map(|m| m.ok), Some(1));
+    assert_eq!(same.parent_id.as_deref(), Some(original.id.as_str()));
+
+    // Only the listed file changed; the checksum file itself did not.
+    std::fs::write(dir.join("a.txt"), b"abd").unwrap();
+    let changed = file_ops::reverify(&original).unwrap();
+    assert_eq!(changed.reverify, Some(ReverifyOutcome::Changed));
+    assert_eq!(changed.status, VerificationStatus::Failed);
+    assert_eq!(changed.manifest.as_ref().map(|m| m.failed), Some(1));
+
+    // Progress counts the bytes of the listed files; cancelling stops the check.
+    let mut seen = 0;
+    file_ops::reverify_with_progress(&original, &mut |b| seen = b, None).unwrap();
+    assert_eq!(seen, 3);
+    let cancel = verifile::hashers::CancelToken::default();
+    cancel.cancel();
+    let err = file_ops::reverify_with_progress(&original, &mut |_| {}, Some(&cancel)).unwrap_err();
+    assert!(err.is::<verifile::hashers::Cancelled>());
+    let stopped = file_ops::cancelled_reverify_record(&original);
+    assert_eq!(stopped.status, VerificationStatus::Cancelled);
+    assert_eq!(stopped.parent_id.as_deref(), Some(original.id.as_str()));
+
+    let cancelled = file_ops::cancelled_record(&dir.join("a.txt"), &Algorithm::Sha256, None);
+    assert!(file_ops::reverify(&cancelled).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
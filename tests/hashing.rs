@@ -0,0 +1,172 @@
+use verifile::hashers;
+use verifile::Algorithm;
+
+/// Digests of the ASCII string "abc" from the published test vectors
+/// (FIPS 180-4, FIPS 202, RFC 1321, RFC 7693, the BLAKE3 and xxHash
+/// reference implementations).
+const ABC_VECTORS: &[(Algorithm, &str)] = &[
+    (Algorithm::Md5, "900150983cd24fb0d6963f7d28e17f72"),
+    (Algorithm::Sha1, "a9993e364706816aba3e25717850c26c9cd0d89d"),
+    (Algorithm::Sha224, "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"),
+    (Algorithm::Sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+    (
+        Algorithm::Sha384,
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+    ),
+    (
+        Algorithm::Sha512,
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+    ),
+    (Algorithm::Sha512_256, "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"),
+    (Algorithm::Sha3_224, "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf"),
+    (Algorithm::Sha3_256, "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"),
+    (
+        Algorithm::Sha3_384,
+        "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+    ),
+    (
+        Algorithm::Sha3_512,
+        "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+    ),
+    (
+        Algorithm::Blake2b512,
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+    ),
+    (Algorithm::Blake2s256, "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"),
+    (Algorithm::Blake3, "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"),
+    (Algorithm::Keccak256, "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"),
+    (Algorithm::Crc32, "352441c2"),
+    (Algorithm::Crc32c, "364b3fb7"),
+    (Algorithm::Adler32, "024d0127"),
+    (Algorithm::Xxh3_64, "78af5f94892f3950"),
+    (Algorithm::Xxh3_128, "06b05ab6733a618578af5f94892f3950"),
+];
+
+#[test]
+fn abc_vectors() {
+    for (algo, expected) in ABC_VECTORS {
+        let got = hashers::compute_hash_for_reader(&b"abc"[..], algo).unwrap();
+        assert_eq!(&got, expected, "{}", algo.name());
+        assert_eq!(got.len(), algo.output_len() * 2, "{}", algo.name());
+    }
+}
+
+/// Digests of the empty message for the SHA-1, SHA-2, SHA-3, BLAKE2 and
+/// Keccak families, from the same sources.
+const EMPTY_VECTORS: &[(Algorithm, &str)] = &[
+    (Algorithm::Sha1, "da39a3ee5e6b4b0d3255bfef95601890afd80709"),
+    (Algorithm::Sha224, "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f"),
+    (
+        Algorithm::Sha384,
+        "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b",
+    ),
+    (Algorithm::Sha512_256, "c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a"),
+    (Algorithm::Sha3_224, "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7"),
+    (Algorithm::Sha3_256, "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"),
+    (
+        Algorithm::Sha3_384,
+        "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004",
+    ),
+    (
+        Algorithm::Sha3_512,
+        "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+    ),
+    (
+        Algorithm::Blake2b512,
+        "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce",
+    ),
+    (Algorithm::Blake2s256, "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9"),
+    (Algorithm::Keccak256, "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+];
+
+/// Digests of the 448-bit message from the FIPS 180-4 and FIPS 202 examples.
+const FIPS_448_MESSAGE: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+const FIPS_448_VECTORS: &[(Algorithm, &str)] = &[
+    (Algorithm::Sha1, "84983e441c3bd26ebaae4aa1f95129e5e54670f1"),
+    (Algorithm::Sha224, "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525"),
+    (
+        Algorithm::Sha384,
+        "3391fdddfc8dc7393707a65b1b4709397cf8b1d162af05abfe8f450de5f36bc6b0455a8520bc4e6f5fe95b1fe3c8452b",
+    ),
+    (Algorithm::Sha512_256, "bde8e1f9f19bb9fd3406c90ec6bc47bd36d8ada9f11880dbc8a22a7078b6a461"),
+    (Algorithm::Sha3_224, "8a24108b154ada21c9fd5574494479ba5c7e7ab76ef264ead0fcce33"),
+    (Algorithm::Sha3_256, "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376"),
+    (
+        Algorithm::Sha3_384,
+        "991c665755eb3a4b6bbdfb75c78a492e8c56a22c5c4d7e429bfdbc32b9d4ad5aa04a1f076e62fea19eef51acd0657c22",
+    ),
+    (
+        Algorithm::Sha3_512,
+        "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636dee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e",
+    ),
+];
+
+#[test]
+fn empty_and_fips_448_vectors() {
+    for (message, vectors) in [(&b""[..], EMPTY_VECTORS), (FIPS_448_MESSAGE, FIPS_448_VECTORS)] {
+        for (algo, expected) in vectors {
+            let got = hashers::compute_hash_for_reader(message, algo).unwrap();
+            assert_eq!(&got, expected, "{} of {} bytes", algo.name(), message.len());
+        }
+    }
+}
+
+#[test]
+fn every_registered_algorithm_has_a_vector() {
+    for algo in Algorithm::all() {
+        assert!(ABC_VECTORS.iter().any(|(a, _)| *a == algo), "no vector for {}", algo.name());
+    }
+}
+
+#[test]
+fn check_string_vectors() {
+    // The "123456789" check values from the CRC catalogue and xxHash.
+    let cases = [
+        (Algorithm::Crc32, "cbf43926"),
+        (Algorithm::Crc32c, "e3069283"),
+        (Algorithm::Adler32, "091e01de"),
+    ];
+    for (algo, expected) in cases {
+        assert_eq!(hashers::compute_hash_for_reader(&b"123456789"[..], &algo).unwrap(), expected);
+    }
+}
+
+#[test]
+fn single_pass_matches_individual_hashes() {
+    let data = vec![0x5au8; 200_000];
+    let algos = Algorithm::all();
+    let all = hashers::compute_hashes_for_reader(&data[..], &algos).unwrap();
+    assert_eq!(all.len(), algos.len());
+    for algo in &algos {
+        assert_eq!(all[algo], hashers::compute_hash_for_reader(&data[..], algo).unwrap());
+    }
+}
+
+#[test]
+fn names_and_aliases_round_trip() {
+    for algo in Algorithm::all() {
+        assert_eq!(algo.name().parse::<Algorithm>().unwrap(), algo);
+    }
+    assert_eq!("sha256".parse::<Algorithm>().unwrap(), Algorithm::Sha256);
+    assert!("sha257".parse::<Algorithm>().is_err());
+    // XXH64 (`xxhsum -H1`) is a different algorithm from XXH3-64.
+    assert!("xxh64".parse::<Algorithm>().is_err());
+}
+
+#[test]
+fn progress_reports_running_byte_count() {
+    let data = vec![0u8; 150_000];
+    let mut seen = Vec::new();
+    hashers::compute_hashes_with_progress(&data[..], &[Algorithm::Sha256], &mut |b| seen.push(b), None).unwrap();
+    assert!(seen.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(seen.last(), Some(&150_000));
+}
+
+#[test]
+fn cancelled_token_stops_the_hash() {
+    let cancel = hashers::CancelToken::default();
+    cancel.cancel();
+    let err = hashers::compute_hashes_with_progress(&[0u8; 1024][..], &[Algorithm::Sha256], &mut |_| {}, Some(&cancel))
+        .unwrap_err();
+    assert!(err.is::<hashers::Cancelled>());
+}
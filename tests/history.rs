@@ -0,0 +1,78 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::records;
+use verifile::history::{self, HistoryQuery, SortKey};
+use verifile::{Algorithm, VerificationStatus};
+
+#[test]
+fn default_query_pages_newest_first() {
+    let recs = records(120);
+    let page = history::query(&recs, &HistoryQuery::default());
+    assert_eq!((page.total, page.page, page.page_count), (120, 0, 3));
+    assert_eq!(page.indices.len(), 50);
+    assert_eq!(page.indices[0], 119);
+
+    let last = history::query(&recs, &HistoryQuery { page: 99, ..Default::default() });
+    assert_eq!(last.page, 2);
+    assert_eq!(last.indices.len(), 20);
+}
+
+#[test]
+fn filters_combine() {
+    let recs = records(100);
+    let q = HistoryQuery {
+        status: Some(VerificationStatus::Failed),
+        algorithm: Some(Algorithm::Sha256),
+        text: "SET-1".to_string(),
+        ..Default::default()
+    };
+    let page = history::query(&recs, &q);
+    // i % 10 == 0 and i % 3 == 1: 10, 40, 70
+    let mut ids: Vec<_> = page.indices.iter().map(|&i| recs[i].id.as_str()).collect();
+    ids.sort();
+    assert_eq!(ids, ["10", "40", "70"]);
+
+    let by_hash = HistoryQuery { text: format!("{:x}", 0x2a), ..Default::default() };
+    assert!(history::query(&recs, &by_hash).indices.iter().any(|&i| recs[i].id == "42"));
+}
+
+#[test]
+fn date_bounds_are_inclusive_days() {
+    let recs = records(100);
+    let day = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+    let q = HistoryQuery { from: Some(day), to: Some(day), ..Default::default() };
+    let page = history::query(&recs, &q);
+    // Records are six hours apart, so any local day holds exactly four.
+    assert_eq!(page.total, 4);
+}
+
+#[test]
+fn sorts_by_column() {
+    let recs = records(20);
+    let q = HistoryQuery { sort: SortKey::FileName, descending: false, ..Default::default() };
+    let page = history::query(&recs, &q);
+    assert_eq!(page.indices, (0..20).collect::<Vec<_>>());
+
+    let q = HistoryQuery { sort: SortKey::Status, descending: false, ..Default::default() };
+    let page = history::query(&recs, &q);
+    assert_eq!(recs[page.indices[0]].status, VerificationStatus::Failed);
+    // Ties keep newest first.
+    assert_eq!(page.indices[0], 15);
+}
+
+#[test]
+fn status_parses_from_cli_spelling() {
+    assert_eq!("in-progress".parse::<VerificationStatus>().unwrap(), VerificationStatus::InProgress);
+    assert_eq!("FAILED".parse::<VerificationStatus>().unwrap(), VerificationStatus::Failed);
+    assert!("maybe".parse::<VerificationStatus>().is_err());
+}
+
+#[test]
+fn error_filter_matches_any_error() {
+    let mut recs = records(3);
+    recs[1].status = VerificationStatus::Error { kind: "PermissionDenied".into(), message: "Permission denied".into() };
+    let q = HistoryQuery { status: Some("error".parse().unwrap()), ..HistoryQuery::default() };
+    let page = history::query(&recs, &q);
+    assert_eq!(page.indices, [1]);
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use common::scratch_dir;
+use chrono::{Local, TimeZone, Utc};
+use std::fs;
+use std::path::PathBuf;
+use verifile::import::{import_log, ImportFormat, ImportOptions};
+use verifile::manifest;
+use verifile::storage::History;
+use verifile::{Algorithm, VerificationStatus};
+
+const SHA256_A: &str = "98ea6e4f216f2fb4b69fff9b3a44842c38686ca685f3f55dc48c5d3fb1107be4";
+const SHA256_B: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+const SHA512_A: &str = "162b0b32f02482d5aca0a7c93dd03ceac3acd7e410a5f18f3fb990fc958ae0df6f32233b91831eaf99ca581a8c4ddf9c8ba315ac482db6d4ea01cc7884a635be";
+const SHA512_B: &str = "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e";
+
+fn opts() -> ImportOptions {
+    ImportOptions {
+        algorithm: Algorithm::Sha256,
+        base_dir: PathBuf::from("/srv/logs"),
+        default_time: Utc.with_ymd_and_hms(2020, 6, 1, 0, 0, 0).unwrap(),
+        manifest: None,
+    }
+}
+
+#[test]
+fn detects_formats() {
+    assert_eq!(ImportFormat::detect("a.iso: OK\nb.iso: FAILED\n"), Some(ImportFormat::ShaSumCheck));
+    assert_eq!(ImportFormat::detect("%%%% HASHDEEP-1.0\n%%%% size,md5,filename\n"), Some(ImportFormat::Hashdeep));
+    assert_eq!(ImportFormat::detect("; Generated by RHash v1.4.2 on 2023-01-15 at 10:12.33\n"), Some(ImportFormat::Rhash));
+    assert_eq!(ImportFormat::detect("hello world\n"), None);
+}
+
+#[test]
+fn tells_check_logs_from_listings() {
+    assert!(verifile::import::is_check_log("a.iso: OK\n"));
+    assert!(verifile::import::is_check_log("--( Verifying SUMS )----\na.iso  OK\n"));
+    assert!(!verifile::import::is_check_log("; Generated by RHash v1.4.2 on 2023-01-15 at 10:12.33\na.txt 3F8AC2EB\n"));
+    assert!(!verifile::import::is_check_log("%%%% HASHDEEP-1.0\n"));
+}
+
+#[test]
+fn reads_sum_check_output_with_its_manifest() {
+    let log = "a.txt: OK\nsha512sum: gone.txt: No such file or directory\ngone.txt: FAILED open or read\nb.txt: FAILED\nsha512sum: WARNING: 1 listed file could not be read\n";
+    let mut o = opts();
+    o.manifest = Some(manifest::parse_checksum_text(&format!("{}  a.txt\n{}  b.txt\n", SHA512_A, SHA512_B), None));
+    let out = import_log(log, ImportFormat::ShaSumCheck, &o);
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+    let r = &out.records;
+    assert_eq!(r.len(), 3);
+    assert_eq!(r[0].file_path, PathBuf::from("/srv/logs/a.txt"));
+    assert_eq!(r[0].status, VerificationStatus::Success);
+    assert_eq!(r[0].computed_hash, SHA512_A);
+    assert_eq!(r[1].status, VerificationStatus::Missing);
+    assert_eq!(r[2].status, VerificationStatus::Failed);
+    assert_eq!(r[2].reference_hash.as_deref(), Some(SHA512_B));
+    assert!(r[2].computed_hash.is_empty());
+    // The tool name in the warning lines gives the algorithm.
+    assert_eq!(r[2].algorithm, Algorithm::Sha512);
+    assert_eq!(r[0].timestamp, o.default_time);
+}
+
+#[test]
+fn keeps_read_errors_apart_from_mismatches() {
+    let log = "sha256sum: secret.key: Permission denied\nsecret.key: FAILED open or read\n";
+    let out = import_log(log, ImportFormat::ShaSumCheck, &opts());
+    match &out.records[0].status {
+        VerificationStatus::Error { kind, message } => {
+            assert_eq!(kind, "PermissionDenied");
+            assert_eq!(message, "Permission denied");
+        }
+        other => panic!("expected an error status, got {:?}", other),
+    }
+}
+
+#[test]
+fn reads_hashdeep_listing() {
+    let log = format!(
+        "%%%% HASHDEEP-1.0\n%%%% size,md5,sha256,filename\n## Invoked from: /home/me\n## $ hashdeep -r data\n##\n\
+         2,764efa883dda1e11db47671c4a3bbd9e,{},/home/me/data/a.txt\n0,d41d8cd98f00b204e9800998ecf8427e,{},data/odd, name.txt\n",
+        SHA256_A, SHA256_B
+    );
+    let out = import_log(&log, ImportFormat::Hashdeep, &opts());
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+    assert_eq!(out.records.len(), 2);
+    let a = &out.records[0];
+    // A listing compares against nothing.
+    assert_eq!(a.status, VerificationStatus::Unverified);
+    assert_eq!(a.algorithm, Algorithm::Sha256);
+    assert_eq!(a.computed_hash, SHA256_A);
+    assert_eq!(a.digests.len(), 2);
+    assert_eq!(out.records[1].file_path, PathBuf::from("/home/me/data/odd, name.txt"));
+}
+
+#[test]
+fn reads_rhash_sfv_with_its_timestamp() {
+    let log = "; Generated by RHash v1.4.2 on 2023-01-15 at 10:12.33\n;          2  10:00.00 2023-01-15 a.txt\na.txt 3F8AC2EB\n";
+    let out = import_log(log, ImportFormat::Rhash, &opts());
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+    let rec = &out.records[0];
+    assert_eq!(rec.algorithm, Algorithm::Crc32);
+    assert_eq!(rec.computed_hash, "3f8ac2eb");
+    assert!(rec.integrity_only);
+    let expected = Local.with_ymd_and_hms(2023, 1, 15, 10, 12, 33).unwrap().with_timezone(&Utc);
+    assert_eq!(rec.timestamp, expected);
+}
+
+#[test]
+fn reads_rhash_check_log() {
+    let log = "\n--( Verifying SHA256SUMS )-----------------------------------\n\
+               a.txt                                         OK\n\
+               b.txt                                         ERR\n\
+               c.txt                                         No such file or directory\n\
+               -------------------------------------------------------------\n\
+               Errors Occurred: Errors:1   Miss:1   Success:1   Total:3\n";
+    let out = import_log(log, ImportFormat::Rhash, &opts());
+    let statuses: Vec<_> = out.records.iter().map(|r| r.status.clone()).collect();
+    assert_eq!(statuses, [VerificationStatus::Success, VerificationStatus::Failed, VerificationStatus::Missing]);
+    assert_eq!(out.records[2].file_name, "c.txt");
+}
+
+#[test]
+fn imports_each_log_once() {
+    let dir = scratch_dir("import-once");
+    let log = dir.join("check.log");
+    fs::write(&log, "a.txt: OK\nb.txt: OK\n").unwrap();
+
+    let history = History::open(&dir.join("history.sqlite3")).unwrap();
+    let parsed = verifile::import::import_file(&log, None, ImportOptions::for_log(&log)).unwrap();
+    assert_eq!(history.import_log_once(&log, &parsed.records).unwrap(), Some(2));
+    assert_eq!(history.import_log_once(&log, &parsed.records).unwrap(), None);
+    assert_eq!(history.load_all().unwrap().len(), 2);
+    assert!(history.verify_chain().unwrap().is_intact());
+    drop(history);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn stores_non_hex_digests_as_hex() {
+    let b64 = "mOpuTyFvL7S2n/+bOkSELDhobKaF8/VdxIxdP7EQe+Q=";
+    let mut o = opts();
+    o.manifest = Some(manifest::parse_checksum_text(&format!("SHA256 (a.txt) = {}\n", b64), None));
+    let out = import_log("a.txt: OK\n", ImportFormat::ShaSumCheck, &o);
+    assert_eq!(out.records[0].reference_hash.as_deref(), Some(b64));
+    assert_eq!(out.records[0].computed_hash, SHA256_A);
+
+    let out = import_log(&format!("SHA256 (a.txt) = {}\n", b64), ImportFormat::Rhash, &opts());
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+    assert_eq!(out.records[0].computed_hash, SHA256_A);
+}
+
+#[test]
+fn only_files_named_in_the_manifest_get_its_digest() {
+    let mut o = opts();
+    o.manifest = Some(manifest::parse_checksum_text(&format!("{}  a.txt\n", SHA256_A), None));
+    let out = import_log("a.txt: OK\nb.txt: OK\n", ImportFormat::ShaSumCheck, &o);
+    assert_eq!(out.records[0].computed_hash, SHA256_A);
+    assert_eq!(out.records[1].reference_hash, None);
+    assert!(out.records[1].computed_hash.is_empty());
+}
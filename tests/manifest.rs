@@ -0,0 +1,173 @@
+mod common;
+
+use common::scratch_dir;
+use std::fs;
+use std::path::Path;
+use verifile::manifest::{self, EntryStatus, GenerateOptions, ManifestFormat};
+use verifile::{Algorithm, VerificationStatus};
+
+const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+fn write(dir: &Path, name: &str, contents: &str) {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn parses_gnu_bsd_and_bare_lines() {
+    let text = format!(
+        "# comment\n{d}  plain.txt\n{d} *binary.iso\n\\{d}  back\\\\slash\nSHA256 (tagged.txt) = {d}\n{d}\nnot a checksum line\n",
+        d = ABC_SHA256
+    );
+    let parsed = manifest::parse_checksum_text(&text, None);
+    let names: Vec<_> = parsed.entries.iter().map(|e| e.file_name.as_deref()).collect();
+    assert_eq!(
+        names,
+        [Some("plain.txt"), Some("binary.iso"), Some("back\\slash"), Some("tagged.txt"), None]
+    );
+    assert!(parsed.entries[1].binary);
+    assert_eq!(parsed.entries[3].algorithm, Some(Algorithm::Sha256));
+    assert_eq!(parsed.entries[0].line, 2);
+    assert_eq!(parsed.diagnostics.len(), 1);
+    assert_eq!(parsed.diagnostics[0].line, 7);
+
+    assert_eq!(parsed.entry_for("tagged.txt").unwrap().line, 5);
+}
+
+#[test]
+fn parser_handles_escapes_crlf_and_legacy_layout() {
+    let text = format!(
+        "{d}  a.txt\r\n\\{d}  new\\nline.txt\nlegacy.txt {d}\n\\{d}  bad\\q\nMD9 (x) = {d}\n",
+        d = ABC_SHA256
+    );
+    let parsed = manifest::parse_checksum_text(&text, None);
+    let names: Vec<_> = parsed.entries.iter().map(|e| e.file_name.as_deref().unwrap()).collect();
+    assert_eq!(names, ["a.txt", "new\nline.txt", "legacy.txt"]);
+    let lines: Vec<_> = parsed.diagnostics.iter().map(|d| d.line).collect();
+    assert_eq!(lines, [4, 5]);
+    assert!(parsed.diagnostics[1].message.contains("unknown algorithm"));
+}
+
+#[test]
+fn gnu_line_whose_name_looks_like_a_tag() {
+    let parsed = manifest::parse_checksum_text(&format!("{}  report (1) = final.pdf\n", ABC_SHA256), None);
+    assert!(parsed.diagnostics.is_empty(), "{:?}", parsed.diagnostics);
+    assert_eq!(parsed.entries[0].file_name.as_deref(), Some("report (1) = final.pdf"));
+    assert_eq!(parsed.entries[0].digest, ABC_SHA256);
+}
+
+#[test]
+fn hint_rejects_digests_of_the_wrong_length() {
+    let text = "a9993e364706816aba3e25717850c26c9cd0d89d  a.txt\n";
+    assert_eq!(manifest::parse_checksum_text(text, None).entries.len(), 1);
+    let parsed = manifest::parse_checksum_text(text, Some(&Algorithm::Sha256));
+    assert!(parsed.entries.is_empty());
+    assert!(parsed.diagnostics[0].message.contains("SHA-256"));
+}
+
+#[test]
+fn entry_lookup_by_name_then_basename() {
+    let text = format!("{d}  dist/app.iso\n{d}  notes.txt\n", d = ABC_SHA256);
+    let parsed = manifest::parse_checksum_text(&text, None);
+    assert_eq!(parsed.entry_for("dist/app.iso").unwrap().line, 1);
+    assert_eq!(parsed.entry_for("app.iso").unwrap().line, 1);
+    assert!(parsed.entry_for("other.iso").is_none());
+    // A single-entry file matches a renamed download.
+    let single = manifest::parse_checksum_text(&format!("{}  app.iso\n", ABC_SHA256), None);
+    assert_eq!(single.entry_for("app (1).iso").unwrap().line, 1);
+}
+
+#[test]
+fn algorithm_hint_from_file_name() {
+    assert_eq!(manifest::algorithm_hint_from_path(Path::new("SHA256SUMS")), Some(Algorithm::Sha256));
+    assert_eq!(manifest::algorithm_hint_from_path(Path::new("x.iso.sha512")), Some(Algorithm::Sha512));
+    assert_eq!(manifest::algorithm_hint_from_path(Path::new("B2SUMS")), Some(Algorithm::Blake2b512));
+}
+
+#[test]
+fn verify_reports_ok_failed_and_missing() {
+    let dir = scratch_dir("verify");
+    write(&dir, "good.txt", "abc");
+    write(&dir, "bad.txt", "abd");
+    write(
+        &dir,
+        "SHA256SUMS",
+        &format!("{d}  good.txt\n{d}  bad.txt\n{d}  gone.txt\n", d = ABC_SHA256),
+    );
+
+    let report = manifest::verify_manifest(&dir.join("SHA256SUMS")).unwrap();
+    let statuses: Vec<_> = report.results.iter().map(|r| r.status).collect();
+    assert_eq!(statuses, [EntryStatus::Ok, EntryStatus::Failed, EntryStatus::Missing]);
+
+    let summary = report.summary();
+    assert_eq!((summary.total, summary.ok, summary.failed, summary.missing), (3, 1, 1, 1));
+    assert_eq!(report.history_record().status, VerificationStatus::Failed);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn generated_manifests_verify_cleanly() {
+    let dir = scratch_dir("generate");
+    write(&dir, "tree/a.txt", "abc");
+    write(&dir, "tree/sub/b.log", "hello");
+    write(&dir, "tree/sub/c.txt", "world");
+
+    let opts = GenerateOptions {
+        algorithm: Algorithm::Sha256,
+        include: Vec::new(),
+        exclude: vec!["*.log".to_string()],
+        absolute_paths: false,
+    };
+    let lines = manifest::generate_manifest(&[dir.join("tree")], &opts).unwrap();
+    let paths: Vec<_> = lines.iter().map(|l| l.path.as_str()).collect();
+    assert_eq!(paths, ["a.txt", "sub/c.txt"]);
+    assert_eq!(lines[0].digest, ABC_SHA256);
+
+    for format in [ManifestFormat::Gnu, ManifestFormat::BsdTag] {
+        let text = manifest::render_manifest(&lines, &opts.algorithm, format).unwrap();
+        let report = manifest::verify_manifest_text(&text, Path::new("SUMS"), &dir.join("tree")).unwrap();
+        assert!(report.diagnostics.is_empty(), "{:?}", format);
+        assert!(report.results.iter().all(|r| r.status == EntryStatus::Ok), "{:?}", format);
+        assert_eq!(report.results.len(), 2);
+    }
+
+    let json = manifest::render_manifest(&lines, &opts.algorithm, ManifestFormat::Json).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["algorithm"], "SHA-256");
+    assert_eq!(value["files"][1]["path"], "sub/c.txt");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn generate_rejects_clashing_names_but_merges_repeats() {
+    let dir = scratch_dir("generate-clash");
+    write(&dir, "dirA/README", "abc");
+    write(&dir, "dirB/README", "abd");
+    let opts = GenerateOptions { algorithm: Algorithm::Sha256, include: Vec::new(), exclude: Vec::new(), absolute_paths: false };
+
+    let err = manifest::collect_files(&[dir.join("dirA"), dir.join("dirB")], &opts).unwrap_err();
+    assert!(err.to_string().contains("README"), "{:#}", err);
+
+    // The same file named twice is listed once.
+    let files = manifest::collect_files(&[dir.join("dirA"), dir.join("dirA/README")], &opts).unwrap();
+    assert_eq!(files.len(), 1);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn recognises_checksum_file_names() {
+    for name in ["SHA256SUMS", "image.iso.sha256", "x.md5", "B2SUMS", "checksums.txt", "MD5SUMS.txt", "release.sum"] {
+        assert!(manifest::looks_like_checksum_file(Path::new(name)), "{}", name);
+    }
+    for name in ["image.iso", "notes.txt", "album.flac"] {
+        assert!(!manifest::looks_like_checksum_file(Path::new(name)), "{}", name);
+    }
+}
+
+#[test]
+fn xxh64_is_not_read_as_xxh3() {
+    assert_eq!(manifest::algorithm_hint_from_path(Path::new("files.xxh64")), None);
+    let parsed = manifest::parse_checksum_text("XXH64 (a.bin) = 44bc2cf5ad770999\n", None);
+    assert!(parsed.entries.iter().all(|e| e.algorithm != Some(Algorithm::Xxh3_64)), "{:?}", parsed.entries);
+}
@@ -0,0 +1,18 @@
+use std::time::Duration;
+use verifile::progress::{self, Progress};
+
+#[test]
+fn fraction_and_formatting() {
+    let mut p = Progress::new(Some(2_000));
+    p.update(500);
+    assert_eq!(p.fraction(), Some(0.25));
+    assert_eq!(Progress::new(None).fraction(), None);
+    assert_eq!(Progress::new(Some(0)).fraction(), Some(1.0));
+
+    assert_eq!(progress::format_bytes(999), "999 B");
+    assert_eq!(progress::format_bytes(1_500_000), "1.5 MB");
+    assert_eq!(progress::format_bytes(40_000_000_000), "40.0 GB");
+    assert_eq!(progress::format_duration(Duration::from_secs(42)), "42s");
+    assert_eq!(progress::format_duration(Duration::from_secs(112)), "1m 52s");
+    assert_eq!(progress::format_duration(Duration::from_secs(7_260)), "2h 1m");
+}
@@ -0,0 +1,98 @@
+use verifile::detect;
+use verifile::encoding;
+use verifile::{Algorithm, HashEncoding};
+
+const HELLO_WORLD_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+#[test]
+fn matches_every_supported_encoding() {
+    let cases = [
+        (ABC_SHA256.to_string(), HashEncoding::Hex),
+        (ABC_SHA256.to_uppercase(), HashEncoding::Hex),
+        (format!("sha256:{}", ABC_SHA256), HashEncoding::Hex),
+        ("ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=".to_string(), HashEncoding::Base64),
+        ("sha256-ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=".to_string(), HashEncoding::Sri),
+        ("sha256:1b8m03r63zqhnjf7l5wnldhh7c134ap5vpj0850ymkq1iyzicy5s".to_string(), HashEncoding::NixBase32),
+        ("XJ4BNP4PAHH6UQKBIDPF3LRCEOYAGYNDSYLXVHFUCD7WD4QACWWQ====".to_string(), HashEncoding::Base32),
+    ];
+    for (reference, encoding) in cases {
+        assert_eq!(
+            detect::match_reference(&reference, &Algorithm::Sha256, ABC_SHA256),
+            Some(encoding),
+            "{}",
+            reference
+        );
+    }
+}
+
+#[test]
+fn cidv0_multihash() {
+    let reference = "QmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4";
+    assert_eq!(detect::candidate_algorithms(reference), vec![Algorithm::Sha256]);
+    assert_eq!(
+        detect::match_reference(reference, &Algorithm::Sha256, HELLO_WORLD_SHA256),
+        Some(HashEncoding::Multihash)
+    );
+}
+
+#[test]
+fn mismatch_and_wrong_label_are_rejected() {
+    let mut wrong = ABC_SHA256.to_string();
+    wrong.replace_range(..1, "c");
+    assert_eq!(detect::match_reference(&wrong, &Algorithm::Sha256, ABC_SHA256), None);
+    // The label says SHA3-256, so a SHA-256 digest must not match even if the bytes do.
+    let labelled = format!("sha3-256:{}", ABC_SHA256);
+    assert_eq!(detect::match_reference(&labelled, &Algorithm::Sha256, ABC_SHA256), None);
+}
+
+#[test]
+fn candidates_follow_digest_length() {
+    assert_eq!(detect::candidate_algorithms("900150983cd24fb0d6963f7d28e17f72"), vec![Algorithm::Md5, Algorithm::Xxh3_128]);
+    assert_eq!(detect::candidate_algorithms("a9993e364706816aba3e25717850c26c9cd0d89d"), vec![Algorithm::Sha1]);
+    assert!(detect::candidate_algorithms(ABC_SHA256).contains(&Algorithm::Blake3));
+    assert_eq!(detect::candidate_algorithms(&format!("blake3:{}", ABC_SHA256)), vec![Algorithm::Blake3]);
+    assert!(detect::candidate_algorithms("not a hash").is_empty());
+    assert!(encoding::decode_reference("   ").is_empty());
+}
+
+#[test]
+fn constant_time_eq() {
+    assert!(encoding::constant_time_eq(b"abc", b"abc"));
+    assert!(!encoding::constant_time_eq(b"abc", b"abd"));
+    assert!(!encoding::constant_time_eq(b"abc", b"ab"));
+}
+
+#[test]
+fn decodes_each_encoding_to_the_digest_bytes() {
+    let digest = hex::decode(ABC_SHA256).unwrap();
+    let cases = [
+        ("ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0", HashEncoding::Base64, None),
+        ("ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0=", HashEncoding::Base64, None),
+        ("xj4bnp4pahh6uqkbidpf3lrceoyagyndsylxvhfucd7wd4qacwwq", HashEncoding::Base32, None),
+        ("sha256-ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=", HashEncoding::Sri, Some(Algorithm::Sha256)),
+        ("sha256:1b8m03r63zqhnjf7l5wnldhh7c134ap5vpj0850ymkq1iyzicy5s", HashEncoding::NixBase32, Some(Algorithm::Sha256)),
+    ];
+    for (reference, encoding, algorithm) in cases {
+        let decoded = encoding::decode_reference(reference);
+        let hit = decoded.iter().find(|d| d.encoding == encoding).unwrap_or_else(|| panic!("{}: {:?}", reference, decoded));
+        assert_eq!(hit.bytes, digest, "{}", reference);
+        assert_eq!(hit.algorithm, algorithm, "{}", reference);
+    }
+}
+
+#[test]
+fn decodes_cidv1_multihash() {
+    // CIDv1 (raw codec, base32) of the SHA-256 of "hello world".
+    let decoded = encoding::decode_reference("bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e");
+    assert_eq!(decoded[0].encoding, HashEncoding::Multihash);
+    assert_eq!(decoded[0].algorithm, Some(Algorithm::Sha256));
+    assert_eq!(hex::encode(&decoded[0].bytes), HELLO_WORLD_SHA256);
+}
+
+#[test]
+fn rejects_lengths_no_algorithm_produces() {
+    assert!(encoding::decode_reference("abcd").is_empty());
+    // A SHA-256 label on a 20-byte digest.
+    assert!(encoding::decode_reference("sha256:a9993e364706816aba3e25717850c26c9cd0d89d").is_empty());
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::{sample, scratch_dir};
+use std::fs;
+use verifile::storage::History;
+use verifile::VerificationStatus;
+
+#[test]
+fn inserts_and_loads_newest_first() {
+    let dir = scratch_dir("storage-insert");
+    let history = History::open(&dir.join("history.sqlite3")).unwrap();
+    for i in 0..5 {
+        history.insert(&sample(i)).unwrap();
+    }
+    let all = history.load_all().unwrap();
+    assert_eq!(all.len(), 5);
+    assert!(all.windows(2).all(|w| w[0].timestamp >= w[1].timestamp));
+
+    // Reopening sees the same rows.
+    drop(history);
+    let history = History::open(&dir.join("history.sqlite3")).unwrap();
+    let id = &all[2].id;
+    let found = history.find_by_id_prefix(&id[..8]).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(&found[0].id, id);
+    assert_eq!(found[0].computed_hash, all[2].computed_hash);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn imports_legacy_json_once() {
+    let dir = scratch_dir("storage-import");
+    let records: Vec<_> = (0..3).map(sample).rev().collect();
+    let json = dir.join("verifications.json");
+    fs::write(&json, serde_json::to_string_pretty(&records).unwrap()).unwrap();
+
+    let history = History::open(&dir.join("history.sqlite3")).unwrap();
+    assert_eq!(history.import_json_once(&json).unwrap(), 3);
+    assert_eq!(history.import_json_once(&json).unwrap(), 0);
+
+    let loaded = history.load_all().unwrap();
+    let ids: Vec<_> = loaded.iter().map(|r| &r.id).collect();
+    let expected: Vec<_> = records.iter().map(|r| &r.id).collect();
+    assert_eq!(ids, expected);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn restores_backup_when_database_is_damaged() {
+    let dir = scratch_dir("storage-recover");
+    let backup = dir.join("history.sqlite3.bak");
+
+    let history = History::open_in(&dir).unwrap();
+    history.insert(&sample(1)).unwrap();
+    fs::remove_file(&backup).unwrap();
+    history.refresh_backup(&backup).unwrap();
+    let backed_up = history.load_all().unwrap().len();
+    let lost = sample(2);
+    history.insert(&lost).unwrap();
+    drop(history);
+
+    fs::write(dir.join("history.sqlite3"), b"this is not a database").unwrap();
+    let history = History::open_in(&dir).unwrap();
+    assert!(history.warnings().iter().any(|w| w.contains("damaged")), "{:?}", history.warnings());
+    let loaded = history.load_all().unwrap();
+    assert_eq!(loaded.len(), backed_up);
+    assert!(loaded.iter().all(|r| r.id != lost.id));
+    let damaged = fs::read_dir(&dir)
+        .unwrap()
+        .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().contains(".damaged-"))
+        .count();
+    assert!(damaged >= 1);
+    drop(history);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn quarantines_unreadable_records() {
+    let dir = scratch_dir("storage-quarantine");
+    let db = dir.join("history.sqlite3");
+    let history = History::open(&db).unwrap();
+    for i in 0..3 {
+        history.insert(&sample(i)).unwrap();
+    }
+    let raw = rusqlite::Connection::open(&db).unwrap();
+    raw.execute(
+        "INSERT INTO records (id, timestamp, file_path, computed_hash, data) VALUES ('broken', 0, 'x', 'x', '{\"id\": 1')",
+        [],
+    )
+    .unwrap();
+    raw.execute(
+        "INSERT INTO records (id, timestamp, file_path, computed_hash, data, record_version) VALUES ('future', 0, 'x', 'x', '{}', 999)",
+        [],
+    )
+    .unwrap();
+
+    let loaded = history.load().unwrap();
+    assert_eq!(loaded.records.len(), 3);
+    assert_eq!(loaded.warnings.len(), 2, "{:?}", loaded.warnings);
+    assert_eq!(history.quarantined().unwrap(), 1);
+
+    // The bad record has moved out; the newer one is still skipped, not lost.
+    let again = history.load().unwrap();
+    assert_eq!(again.records.len(), 3);
+    assert_eq!(again.warnings.len(), 1);
+    let kept: i64 = raw.query_row("SELECT COUNT(*) FROM records WHERE id = 'future'", [], |r| r.get(0)).unwrap();
+    assert_eq!(kept, 1);
+    drop((history, raw));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn import_quarantines_bad_entries() {
+    let dir = scratch_dir("storage-import-bad");
+    let mut entries: Vec<serde_json::Value> = (0..2).map(|i| serde_json::to_value(sample(i)).unwrap()).collect();
+    entries.push(serde_json::json!({ "id": "half-written", "file_name": "x" }));
+    let json = dir.join("verifications.json");
+    fs::write(&json, serde_json::to_string(&entries).unwrap()).unwrap();
+
+    let history = History::open(&dir.join("history.sqlite3")).unwrap();
+    assert_eq!(history.import_json_once(&json).unwrap(), 2);
+    assert_eq!(history.load_all().unwrap().len(), 2);
+    assert_eq!(history.quarantined().unwrap(), 1);
+    drop(history);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn old_records_without_a_reference_read_as_unverified() {
+    let dir = scratch_dir("storage-unverified");
+    let hashed = sample(0);
+    let mut matched = sample(1);
+    matched.reference_hash = Some(matched.computed_hash.clone());
+    let mut values = [serde_json::to_value(&hashed).unwrap(), serde_json::to_value(&matched).unwrap()];
+    // How version 1 stored both of them.
+    for v in &mut values {
+        v["status"] = "Success".into();
+    }
+    let json = dir.join("verifications.json");
+    fs::write(&json, serde_json::to_string(&values).unwrap()).unwrap();
+
+    let history = History::open(&dir.join("history.sqlite3")).unwrap();
+    history.import_json_once(&json).unwrap();
+    let loaded = history.load_all().unwrap();
+    let status = |id: &str| loaded.iter().find(|r| r.id == id).unwrap().status.clone();
+    assert_eq!(status(&hashed.id), VerificationStatus::Unverified);
+    assert_eq!(status(&matched.id), VerificationStatus::Success);
+    assert!(history.verify_chain().unwrap().is_intact());
+    drop(history);
+    fs::remove_dir_all(dir).unwrap();
+}